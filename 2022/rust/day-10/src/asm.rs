@@ -0,0 +1,261 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+};
+
+use crate::cpu::{spec_for, Instruction, Operand, Register};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssemblyError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for AssemblyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AssemblyError {}
+
+// drops comments starting with ; or # and splits off a leading `label:`
+fn split_line(line: &str) -> (Option<&str>, &str) {
+    let code = line.split([';', '#']).next().unwrap_or("").trim();
+
+    match code.split_once(':') {
+        Some((label, rest)) => (Some(label.trim()), rest.trim()),
+        None => (None, code),
+    }
+}
+
+fn is_valid_label(label: &str) -> bool {
+    let mut chars = label.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && label.parse::<Register>().is_err()
+}
+
+fn parse_target(token: &str, labels: &HashMap<String, usize>) -> Result<usize, String> {
+    if let Some(&target) = labels.get(token) {
+        return Ok(target);
+    }
+
+    token
+        .parse::<usize>()
+        .map_err(|_| format!("Unknown label: {}", token))
+}
+
+// parses a single instruction, jump targets are looked up in `labels` or taken as instruction indexes
+pub fn parse_instruction(s: &str, labels: &HashMap<String, usize>) -> Result<Instruction, String> {
+    let mut parts = s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|p| !p.is_empty());
+
+    let command = parts.next().ok_or("Empty command")?;
+    let operands = parts.collect::<Vec<_>>();
+
+    let spec = spec_for(command).ok_or(format!("Unknown command: {}", command))?;
+    if operands.len() != spec.operands {
+        return Err(format!(
+            "{} takes {} operand(s) but got {}",
+            command,
+            spec.operands,
+            operands.len()
+        ));
+    }
+
+    let register = |i: usize| operands[i].parse::<Register>();
+    let operand = |i: usize| operands[i].parse::<Operand>();
+    let target = |i: usize| parse_target(operands[i], labels);
+
+    Ok(match command {
+        "noop" => Instruction::Noop,
+        "addx" => Instruction::AddX(
+            operands[0]
+                .parse::<i32>()
+                .map_err(|_| format!("Expected a number, found: {}", operands[0]))?,
+        ),
+        "set" => Instruction::Set(register(0)?, operand(1)?),
+        "add" => Instruction::Add(register(0)?, operand(1)?),
+        "mul" => Instruction::Mul(register(0)?, operand(1)?),
+        "jmp" => Instruction::Jmp(target(0)?),
+        "jz" => Instruction::Jz(register(0)?, target(1)?),
+        "jnz" => Instruction::Jnz(register(0)?, target(1)?),
+        _ => unreachable!("{} is in the instruction set but not handled", command),
+    })
+}
+
+// two passes: first find out which instruction every label points at, then parse
+pub fn assemble(input: &str) -> Result<Vec<Instruction>, AssemblyError> {
+    let mut labels = HashMap::new();
+    let mut count = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let (label, code) = split_line(line);
+
+        if let Some(label) = label {
+            let error = |message: String| AssemblyError {
+                line: index + 1,
+                message,
+            };
+
+            if !is_valid_label(label) {
+                return Err(error(format!("Invalid label: {}", label)));
+            }
+            if labels.insert(label.to_string(), count).is_some() {
+                return Err(error(format!("Duplicate label: {}", label)));
+            }
+        }
+
+        if !code.is_empty() {
+            count += 1;
+        }
+    }
+
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (_, code) = split_line(line);
+            (!code.is_empty()).then(|| {
+                parse_instruction(code, &labels).map_err(|message| AssemblyError {
+                    line: index + 1,
+                    message,
+                })
+            })
+        })
+        .collect()
+}
+
+impl Display for Instruction {
+    // jump targets are printed as instruction indexes, see disassemble for labels
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        disassemble_one(self, |target| target.to_string(), f)
+    }
+}
+
+fn disassemble_one(
+    instruction: &Instruction,
+    target: impl Fn(usize) -> String,
+    f: &mut impl std::fmt::Write,
+) -> std::fmt::Result {
+    let name = instruction.spec().name;
+    match *instruction {
+        Instruction::Noop => write!(f, "{}", name),
+        Instruction::AddX(value) => write!(f, "{} {}", name, value),
+        Instruction::Set(register, operand)
+        | Instruction::Add(register, operand)
+        | Instruction::Mul(register, operand) => write!(f, "{} {}, {}", name, register, operand),
+        Instruction::Jmp(to) => write!(f, "{} {}", name, target(to)),
+        Instruction::Jz(register, to) | Instruction::Jnz(register, to) => {
+            write!(f, "{} {}, {}", name, register, target(to))
+        }
+    }
+}
+
+// turns a program back into source, naming every jump target `l<index>`.
+// targets further out than just past the end have nowhere to put a label,
+// so they stay raw instruction indexes
+pub fn disassemble(program: &[Instruction]) -> String {
+    let targets = program
+        .iter()
+        .filter_map(|i| i.jump_target())
+        .collect::<Vec<_>>();
+    let label = |index: usize| match index <= program.len() {
+        true => format!("l{}", index),
+        false => index.to_string(),
+    };
+
+    let mut output = String::new();
+    for (index, instruction) in program.iter().enumerate() {
+        if targets.contains(&index) {
+            output.push_str(&format!("{}:\n", label(index)));
+        }
+        disassemble_one(instruction, label, &mut output).unwrap();
+        output.push('\n');
+    }
+
+    // jumping past the last instruction halts the program
+    if targets.contains(&program.len()) {
+        output.push_str(&format!("{}:\n", label(program.len())));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "; count x down from 5 while y collects the sum
+    set x, 5   # comments can go anywhere
+loop:
+    add y, x
+    addx -1
+    jz x, done
+    jmp loop
+done: mul y, 2
+";
+
+    #[test]
+    pub fn test_assemble() {
+        let program = assemble(PROGRAM).unwrap();
+
+        assert_eq!(
+            program,
+            vec![
+                Instruction::Set(Register::X, Operand::Value(5)),
+                Instruction::Add(Register::Y, Operand::Register(Register::X)),
+                Instruction::AddX(-1),
+                Instruction::Jz(Register::X, 5),
+                Instruction::Jmp(1),
+                Instruction::Mul(Register::Y, Operand::Value(2)),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_round_trip() {
+        let program = assemble(PROGRAM).unwrap();
+        let source = disassemble(&program);
+
+        assert_eq!(
+            source,
+            "set x, 5
+l1:
+add y, x
+addx -1
+jz x, l5
+jmp l1
+l5:
+mul y, 2
+"
+        );
+        assert_eq!(assemble(&source).unwrap(), program);
+    }
+
+    #[test]
+    pub fn test_round_trip_far_jumps() {
+        let program = assemble("jmp 7\njz x, 2").unwrap();
+        let source = disassemble(&program);
+
+        assert_eq!(source, "jmp 7\njz x, l2\nl2:\n");
+        assert_eq!(assemble(&source).unwrap(), program);
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let error = |source: &str| assemble(source).unwrap_err().to_string();
+
+        assert_eq!(error("noop\nfoo 1"), "line 2: Unknown command: foo");
+        assert_eq!(
+            error("noop\n\njmp nowhere"),
+            "line 3: Unknown label: nowhere"
+        );
+        assert_eq!(error("add q, 1"), "line 1: Unknown register: q");
+        assert_eq!(error("addx"), "line 1: addx takes 1 operand(s) but got 0");
+        assert_eq!(error("a: noop\na: noop"), "line 2: Duplicate label: a");
+        assert_eq!(error("x: noop"), "line 1: Invalid label: x");
+    }
+}
@@ -0,0 +1,46 @@
+use day_10::{
+    asm::assemble,
+    cpu::{Cpu, Register},
+    crt::{Crt, CrtConfig},
+};
+use std::{env, fs, process};
+
+// assembles and runs your own program for at most one frame, then shows what
+// ended up on the crt
+// cargo run --bin crt -- program.asm [width] [height] [sprite width]
+fn main() {
+    let mut args = env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| "./input.txt".to_string());
+    let mut numbers = args.map(|a| a.parse::<usize>().expect("Not a number"));
+
+    let default = CrtConfig::default();
+    let config = CrtConfig {
+        width: numbers.next().unwrap_or(default.width),
+        height: numbers.next().unwrap_or(default.height),
+        sprite_width: numbers.next().unwrap_or(default.sprite_width),
+    };
+
+    let source = fs::read_to_string(path).expect("Unable to read file");
+    let program = assemble(&source).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+
+    let mut cpu = Cpu::new(program);
+    let mut crt = Crt::new(config);
+    // programs that loop forever would never get to the screen otherwise
+    let frame = config.width * config.height;
+    while cpu.cycles < frame && cpu.step(&mut [&mut crt]) {}
+    if !cpu.is_halted() {
+        println!(
+            "Still running after one frame, stopped at cycle {}\n",
+            cpu.cycles
+        );
+    }
+
+    println!("{}\n", crt.render());
+    for register in Register::ALL {
+        println!("{} = {}", register, cpu.registers.get(register));
+    }
+    println!("{} cycles", cpu.cycles);
+}
@@ -0,0 +1,42 @@
+use day_10::{
+    asm::assemble,
+    debugger::{Debugger, HELP},
+};
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    process,
+};
+
+// cargo run --bin debug -- [program]
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "./input.txt".to_string());
+    let source = fs::read_to_string(path).expect("Unable to read file");
+
+    let program = assemble(&source).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+
+    let mut debugger = Debugger::new(program);
+    println!("{}\n\n{}", HELP, debugger.status());
+
+    let stdin = io::stdin();
+    loop {
+        print!("\n(debug) ");
+        io::stdout().flush().unwrap();
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+
+        match line.trim() {
+            "q" | "quit" => break,
+            "h" | "help" => println!("{}", HELP),
+            command => println!("{}", debugger.execute(command)),
+        }
+    }
+}
@@ -1,8 +1,13 @@
-use day_10::process_part2;
-use std::fs;
+use day_10::{process_part2, process_part2_picture};
+use std::{env, fs};
 
 fn main() {
     let file = fs::read_to_string("./input.txt").expect("Unable to read file");
 
-    println!("Part 2:\n{}", process_part2(&file));
+    // pass --picture to see the screen as well
+    if env::args().any(|a| a == "--picture") {
+        println!("{}\n", process_part2_picture(&file));
+    }
+
+    println!("Part 2: {}", process_part2(&file));
 }
@@ -0,0 +1,393 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    X,
+    Y,
+    Z,
+    W,
+}
+
+impl Register {
+    pub const ALL: [Register; 4] = [Register::X, Register::Y, Register::Z, Register::W];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl FromStr for Register {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "x" => Ok(Register::X),
+            "y" => Ok(Register::Y),
+            "z" => Ok(Register::Z),
+            "w" => Ok(Register::W),
+            _ => Err(format!("Unknown register: {}", s)),
+        }
+    }
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Register::X => "x",
+            Register::Y => "y",
+            Register::Z => "z",
+            Register::W => "w",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registers {
+    values: [i32; Register::ALL.len()],
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        // X starts at 1, everything else at 0
+        let mut registers = Registers {
+            values: [0; Register::ALL.len()],
+        };
+        registers.set(Register::X, 1);
+        registers
+    }
+}
+
+impl Registers {
+    pub fn get(&self, register: Register) -> i32 {
+        self.values[register.index()]
+    }
+
+    pub fn set(&mut self, register: Register, value: i32) {
+        self.values[register.index()] = value;
+    }
+}
+
+// the right hand side of an instruction, either a register or a literal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Value(i32),
+}
+
+impl Operand {
+    pub fn resolve(&self, registers: &Registers) -> i32 {
+        match *self {
+            Operand::Register(register) => registers.get(register),
+            Operand::Value(value) => value,
+        }
+    }
+}
+
+impl FromStr for Operand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<i32>() {
+            Ok(value) => Ok(Operand::Value(value)),
+            Err(_) => s
+                .parse::<Register>()
+                .map(Operand::Register)
+                .map_err(|_| format!("Expected a number or register, found: {}", s)),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+// jump targets are instruction indexes, the assembler turns labels into them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    AddX(i32),
+    Set(Register, Operand),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Jmp(usize),
+    Jz(Register, usize),
+    Jnz(Register, usize),
+}
+
+pub struct InstructionSpec {
+    pub name: &'static str,
+    // cycles the instruction takes, its effect lands at the end of the last one
+    pub cycles: usize,
+    pub operands: usize,
+}
+
+pub const INSTRUCTION_SET: [InstructionSpec; 8] = [
+    InstructionSpec {
+        name: "noop",
+        cycles: 1,
+        operands: 0,
+    },
+    InstructionSpec {
+        name: "addx",
+        cycles: 2,
+        operands: 1,
+    },
+    InstructionSpec {
+        name: "set",
+        cycles: 1,
+        operands: 2,
+    },
+    InstructionSpec {
+        name: "add",
+        cycles: 2,
+        operands: 2,
+    },
+    InstructionSpec {
+        name: "mul",
+        cycles: 3,
+        operands: 2,
+    },
+    InstructionSpec {
+        name: "jmp",
+        cycles: 1,
+        operands: 1,
+    },
+    InstructionSpec {
+        name: "jz",
+        cycles: 1,
+        operands: 2,
+    },
+    InstructionSpec {
+        name: "jnz",
+        cycles: 1,
+        operands: 2,
+    },
+];
+
+pub fn spec_for(name: &str) -> Option<&'static InstructionSpec> {
+    INSTRUCTION_SET.iter().find(|spec| spec.name == name)
+}
+
+impl Instruction {
+    pub fn spec(&self) -> &'static InstructionSpec {
+        let name = match self {
+            Instruction::Noop => "noop",
+            Instruction::AddX(_) => "addx",
+            Instruction::Set(..) => "set",
+            Instruction::Add(..) => "add",
+            Instruction::Mul(..) => "mul",
+            Instruction::Jmp(_) => "jmp",
+            Instruction::Jz(..) => "jz",
+            Instruction::Jnz(..) => "jnz",
+        };
+        spec_for(name).unwrap()
+    }
+
+    pub fn jump_target(&self) -> Option<usize> {
+        match *self {
+            Instruction::Jmp(target) | Instruction::Jz(_, target) | Instruction::Jnz(_, target) => {
+                Some(target)
+            }
+            _ => None,
+        }
+    }
+
+    // applies the instruction, returning where to jump to if it does.
+    // arithmetic wraps like a real 32-bit register instead of panicking
+    fn execute(&self, registers: &mut Registers) -> Option<usize> {
+        match *self {
+            Instruction::Noop => {}
+            Instruction::AddX(value) => {
+                registers.set(Register::X, registers.get(Register::X).wrapping_add(value));
+            }
+            Instruction::Set(register, operand) => {
+                registers.set(register, operand.resolve(registers));
+            }
+            Instruction::Add(register, operand) => {
+                registers.set(
+                    register,
+                    registers
+                        .get(register)
+                        .wrapping_add(operand.resolve(registers)),
+                );
+            }
+            Instruction::Mul(register, operand) => {
+                registers.set(
+                    register,
+                    registers
+                        .get(register)
+                        .wrapping_mul(operand.resolve(registers)),
+                );
+            }
+            Instruction::Jmp(target) => return Some(target),
+            Instruction::Jz(register, target) => {
+                if registers.get(register) == 0 {
+                    return Some(target);
+                }
+            }
+            Instruction::Jnz(register, target) => {
+                if registers.get(register) != 0 {
+                    return Some(target);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// a single instruction without labels, jump targets have to be instruction indexes
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::asm::parse_instruction(s, &Default::default())
+    }
+}
+
+pub fn parse_program(input: &str) -> Vec<Instruction> {
+    crate::asm::assemble(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+// gets to look at the cpu while a cycle is in progress, before the
+// instruction finishing in that cycle has had its effect
+pub trait Observer {
+    fn during(&mut self, cpu: &Cpu);
+}
+
+pub struct Cpu {
+    pub registers: Registers,
+    pub program: Vec<Instruction>,
+    // index of the instruction being executed
+    pub pc: usize,
+    // cycles completed so far
+    pub cycles: usize,
+    // cycles the current instruction has been running for
+    elapsed: usize,
+}
+
+impl Cpu {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Cpu {
+            registers: Registers::default(),
+            program,
+            pc: 0,
+            cycles: 0,
+            elapsed: 0,
+        }
+    }
+
+    pub fn x(&self) -> i32 {
+        self.registers.get(Register::X)
+    }
+
+    // the cycle in progress (or about to start), counting from 1
+    pub fn cycle(&self) -> usize {
+        self.cycles + 1
+    }
+
+    // cycles the current instruction has been running for, 0 when it hasn't started yet
+    pub fn elapsed(&self) -> usize {
+        self.elapsed
+    }
+
+    pub fn current(&self) -> Option<Instruction> {
+        self.program.get(self.pc).copied()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.current().is_none()
+    }
+
+    // runs a single cycle, returns false once the program has finished
+    pub fn step(&mut self, observers: &mut [&mut dyn Observer]) -> bool {
+        let Some(instruction) = self.current() else {
+            return false;
+        };
+
+        for observer in observers.iter_mut() {
+            observer.during(self);
+        }
+
+        self.cycles += 1;
+        self.elapsed += 1;
+
+        if self.elapsed == instruction.spec().cycles {
+            self.pc = instruction
+                .execute(&mut self.registers)
+                .unwrap_or(self.pc + 1);
+            self.elapsed = 0;
+        }
+
+        true
+    }
+
+    pub fn run(&mut self, observers: &mut [&mut dyn Observer]) {
+        while self.step(observers) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Trace(Vec<(usize, i32)>);
+
+    impl Observer for Trace {
+        fn during(&mut self, cpu: &Cpu) {
+            self.0.push((cpu.cycle(), cpu.x()));
+        }
+    }
+
+    #[test]
+    pub fn test_small_program() {
+        let mut cpu = Cpu::new(parse_program("noop\naddx 3\naddx -5"));
+        let mut trace = Trace(vec![]);
+
+        cpu.run(&mut [&mut trace]);
+
+        assert_eq!(trace.0, vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+        assert_eq!(cpu.x(), -1);
+        assert_eq!(cpu.cycles, 5);
+        assert!(!cpu.step(&mut []));
+    }
+
+    #[test]
+    pub fn test_overflow_wraps() {
+        let mut cpu = Cpu::new(parse_program(
+            "set x, 65536\nmul x, x\naddx 2147483647\naddx 1",
+        ));
+
+        cpu.run(&mut []);
+
+        assert_eq!(cpu.x(), i32::MIN);
+    }
+
+    #[test]
+    pub fn test_loop() {
+        // x = 2 * 3 * 3 with a countdown in y
+        let mut cpu = Cpu::new(parse_program(
+            "set x, 2
+set y, 2
+again: mul x, 3
+add y, -1
+jnz y, again",
+        ));
+
+        cpu.run(&mut []);
+
+        assert_eq!(cpu.x(), 18);
+        assert_eq!(cpu.registers.get(Register::Y), 0);
+        // 2 sets, then 2 rounds of mul + add + jnz
+        assert_eq!(cpu.cycles, 2 + 2 * (3 + 2 + 1));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::cpu::{Cpu, Observer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrtConfig {
+    pub width: usize,
+    pub height: usize,
+    // X is the middle pixel of the sprite, rounding left for even widths
+    pub sprite_width: usize,
+}
+
+impl Default for CrtConfig {
+    fn default() -> Self {
+        CrtConfig {
+            width: 40,
+            height: 6,
+            sprite_width: 3,
+        }
+    }
+}
+
+impl CrtConfig {
+    // the columns the sprite covers when centered on `x`
+    pub fn sprite(&self, x: i32) -> (i32, i32) {
+        let start = x.saturating_sub((self.sprite_width as i32 - 1) / 2);
+        (start, start.saturating_add(self.sprite_width as i32 - 1))
+    }
+
+    // where the beam is during a cycle as (column, row), wrapping back to
+    // the top left after the last pixel like a real screen starting a new frame
+    pub fn beam(&self, cycle: usize) -> (usize, usize) {
+        let position = (cycle - 1) % (self.width * self.height);
+        (position % self.width, position / self.width)
+    }
+}
+
+// draws a pixel every cycle, lit when the sprite centered on X covers the beam
+pub struct Crt {
+    pub config: CrtConfig,
+    pub pixels: Vec<bool>,
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Crt::new(CrtConfig::default())
+    }
+}
+
+impl Observer for Crt {
+    fn during(&mut self, cpu: &Cpu) {
+        let (column, row) = self.config.beam(cpu.cycle());
+        let (start, end) = self.config.sprite(cpu.x());
+
+        self.pixels[row * self.config.width + column] = (start..=end).contains(&(column as i32));
+    }
+}
+
+impl Crt {
+    pub fn new(config: CrtConfig) -> Self {
+        assert!(
+            config.width > 0 && config.height > 0,
+            "the screen needs at least one pixel"
+        );
+
+        Crt {
+            config,
+            pixels: vec![false; config.width * config.height],
+        }
+    }
+
+    pub fn render(&self) -> String {
+        self.pixels
+            .chunks(self.config.width)
+            .map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::parse_program;
+
+    #[test]
+    pub fn test_small_screen_wraps() {
+        // 9 cycles on a 4x2 screen, the last one lights up the top left again
+        let mut cpu = Cpu::new(parse_program("addx 2\naddx 1\naddx -3\naddx -1\nnoop"));
+        let mut crt = Crt::new(CrtConfig {
+            width: 4,
+            height: 2,
+            sprite_width: 1,
+        });
+
+        cpu.run(&mut [&mut crt]);
+
+        assert_eq!(cpu.cycles, 9);
+        assert_eq!(crt.render(), "##.#\n....");
+    }
+
+    #[test]
+    pub fn test_sprite_width() {
+        let config = CrtConfig {
+            width: 40,
+            height: 6,
+            sprite_width: 4,
+        };
+        assert_eq!(config.sprite(5), (4, 7));
+        assert_eq!(CrtConfig::default().sprite(5), (4, 6));
+        assert_eq!(config.beam(41), (0, 1));
+        assert_eq!(config.beam(241), (0, 0));
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt::Write;
+
+use crate::{
+    cpu::{Cpu, Instruction, Register},
+    crt::Crt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    // stop before this cycle starts
+    Cycle(usize),
+    // stop before the instruction at this index starts
+    Instruction(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    Changed,
+    Equal(i32),
+    NotEqual(i32),
+    Less(i32),
+    LessOrEqual(i32),
+    Greater(i32),
+    GreaterOrEqual(i32),
+}
+
+// stops when the condition on a register becomes true, or when the value changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watch {
+    pub register: Register,
+    pub condition: Condition,
+}
+
+impl Watch {
+    // `x`, `x > 10`, `y == 3` and so on
+    pub fn parse(expression: &str) -> Result<Watch, String> {
+        let parts = expression.split_whitespace().collect::<Vec<_>>();
+        let register = parts.first().ok_or("Watch what?")?.parse::<Register>()?;
+
+        let condition = match parts[1..] {
+            [] => Condition::Changed,
+            [op, value] => {
+                let value = value
+                    .parse::<i32>()
+                    .map_err(|_| format!("Expected a number, found: {}", value))?;
+                match op {
+                    "==" => Condition::Equal(value),
+                    "!=" => Condition::NotEqual(value),
+                    "<" => Condition::Less(value),
+                    "<=" => Condition::LessOrEqual(value),
+                    ">" => Condition::Greater(value),
+                    ">=" => Condition::GreaterOrEqual(value),
+                    _ => return Err(format!("Unknown operator: {}", op)),
+                }
+            }
+            _ => return Err(format!("Cannot watch: {}", expression)),
+        };
+
+        Ok(Watch {
+            register,
+            condition,
+        })
+    }
+
+    fn holds(&self, value: i32) -> bool {
+        match self.condition {
+            Condition::Changed => false,
+            Condition::Equal(v) => value == v,
+            Condition::NotEqual(v) => value != v,
+            Condition::Less(v) => value < v,
+            Condition::LessOrEqual(v) => value <= v,
+            Condition::Greater(v) => value > v,
+            Condition::GreaterOrEqual(v) => value >= v,
+        }
+    }
+
+    fn triggered(&self, before: i32, after: i32) -> bool {
+        match self.condition {
+            Condition::Changed => before != after,
+            _ => !self.holds(before) && self.holds(after),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stop {
+    Stepped,
+    Halted,
+    Breakpoint(Breakpoint),
+    Watch(Watch, i32, i32),
+}
+
+pub struct Debugger {
+    pub cpu: Cpu,
+    pub crt: Crt,
+    pub breakpoints: Vec<Breakpoint>,
+    pub watches: Vec<Watch>,
+}
+
+impl Debugger {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Debugger {
+            cpu: Cpu::new(program),
+            crt: Crt::default(),
+            breakpoints: vec![],
+            watches: vec![],
+        }
+    }
+
+    fn breakpoint_hit(&self) -> Option<Breakpoint> {
+        self.breakpoints.iter().copied().find(|b| match *b {
+            Breakpoint::Cycle(cycle) => self.cpu.cycle() == cycle,
+            Breakpoint::Instruction(index) => self.cpu.pc == index && self.cpu.elapsed() == 0,
+        })
+    }
+
+    // runs one cycle and reports the first watch it triggered
+    fn cycle(&mut self) -> Option<Stop> {
+        let before = self.cpu.registers.clone();
+        if !self.cpu.step(&mut [&mut self.crt]) {
+            return Some(Stop::Halted);
+        }
+
+        self.watches.iter().find_map(|w| {
+            let (old, new) = (before.get(w.register), self.cpu.registers.get(w.register));
+            w.triggered(old, new).then_some(Stop::Watch(*w, old, new))
+        })
+    }
+
+    pub fn step(&mut self) -> Stop {
+        self.cycle().unwrap_or(Stop::Stepped)
+    }
+
+    // runs until the current instruction has finished
+    pub fn step_instruction(&mut self) -> Stop {
+        let pc = self.cpu.pc;
+        loop {
+            if let Some(stop) = self.cycle() {
+                return stop;
+            }
+            if self.cpu.pc != pc || self.cpu.elapsed() == 0 {
+                return Stop::Stepped;
+            }
+        }
+    }
+
+    // runs until a breakpoint or watch triggers or the program ends
+    pub fn resume(&mut self) -> Stop {
+        loop {
+            if let Some(stop) = self.cycle() {
+                return stop;
+            }
+            if let Some(breakpoint) = self.breakpoint_hit() {
+                return Stop::Breakpoint(breakpoint);
+            }
+        }
+    }
+
+    // where the beam and the sprite are for the cycle about to run
+    pub fn beam_view(&self) -> String {
+        let config = self.crt.config;
+        let (column, _) = config.beam(self.cpu.cycle());
+        let (start, end) = config.sprite(self.cpu.x());
+
+        let sprite = (0..config.width as i32)
+            .map(|c| if (start..=end).contains(&c) { '#' } else { '.' })
+            .collect::<String>();
+        let beam = " ".repeat(column) + "^";
+
+        format!("sprite {}\nbeam   {}", sprite, beam)
+    }
+
+    pub fn status(&self) -> String {
+        let mut output = String::new();
+
+        let instruction = match self.cpu.current() {
+            Some(instruction) => format!(
+                "{} [{}] ({}/{})",
+                self.cpu.pc,
+                instruction,
+                self.cpu.elapsed(),
+                instruction.spec().cycles
+            ),
+            None => "halted".to_string(),
+        };
+        writeln!(output, "cycle {} at {}", self.cpu.cycle(), instruction).unwrap();
+
+        let registers = Register::ALL
+            .iter()
+            .map(|&r| format!("{}={}", r, self.cpu.registers.get(r)))
+            .collect::<Vec<_>>();
+        writeln!(output, "{}", registers.join(" ")).unwrap();
+        write!(output, "{}", self.beam_view()).unwrap();
+
+        output
+    }
+
+    fn describe(&self, stop: Stop) -> String {
+        let reason = match stop {
+            Stop::Stepped => String::new(),
+            Stop::Halted => "program finished\n".to_string(),
+            Stop::Breakpoint(b) => format!("hit breakpoint {:?}\n", b),
+            Stop::Watch(w, old, new) => {
+                format!("watch on {} triggered: {} -> {}\n", w.register, old, new)
+            }
+        };
+        reason + &self.status()
+    }
+
+    // runs a single debugger command and returns what to show for it
+    pub fn execute(&mut self, command: &str) -> String {
+        let parts = command.split_whitespace().collect::<Vec<_>>();
+
+        match parts[..] {
+            [] | ["s"] | ["step"] => {
+                let stop = self.step();
+                self.describe(stop)
+            }
+            ["s" | "step", count] => match count.parse::<usize>() {
+                Ok(count) => {
+                    let mut stop = Stop::Stepped;
+                    for _ in 0..count {
+                        stop = self.step();
+                        if stop != Stop::Stepped {
+                            break;
+                        }
+                    }
+                    self.describe(stop)
+                }
+                Err(_) => format!("Expected a number, found: {}", count),
+            },
+            ["n" | "next"] => {
+                let stop = self.step_instruction();
+                self.describe(stop)
+            }
+            ["c" | "continue"] => {
+                let stop = self.resume();
+                self.describe(stop)
+            }
+            ["b" | "break", kind, value] => {
+                let Ok(value) = value.parse::<usize>() else {
+                    return format!("Expected a number, found: {}", value);
+                };
+                let breakpoint = match kind {
+                    "cycle" => Breakpoint::Cycle(value),
+                    "pc" | "instruction" => Breakpoint::Instruction(value),
+                    _ => return format!("Unknown breakpoint kind: {} (try cycle or pc)", kind),
+                };
+                self.breakpoints.push(breakpoint);
+                format!("breakpoint {}: {:?}", self.breakpoints.len(), breakpoint)
+            }
+            ["w" | "watch", ..] => match Watch::parse(&parts[1..].join(" ")) {
+                Ok(watch) => {
+                    self.watches.push(watch);
+                    format!("watch {}: {:?}", self.watches.len(), watch)
+                }
+                Err(e) => e,
+            },
+            ["d" | "delete"] => {
+                self.breakpoints.clear();
+                self.watches.clear();
+                "deleted all breakpoints and watches".to_string()
+            }
+            ["p" | "print"] => self.status(),
+            ["crt"] => self.crt.render(),
+            _ => format!("Unknown command: {}", command),
+        }
+    }
+}
+
+pub const HELP: &str = "commands:
+  s, step [n]            run one (or n) cycles
+  n, next                finish the current instruction
+  c, continue            run until a breakpoint or watch triggers
+  b, break cycle <n>     stop before cycle n
+  b, break pc <n>        stop before instruction n starts
+  w, watch <reg> [op n]  stop when a register changes, or when e.g. `x > 10` becomes true
+  d, delete              remove all breakpoints and watches
+  p, print               show registers, beam and sprite
+  crt                    show the screen so far
+  q, quit";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::parse_program;
+
+    const PROGRAM: &str = "noop
+addx 3
+addx -5
+noop";
+
+    #[test]
+    pub fn test_breakpoints() {
+        let mut debugger = Debugger::new(parse_program(PROGRAM));
+        debugger.execute("break cycle 3");
+        debugger.execute("break pc 3");
+
+        assert_eq!(debugger.resume(), Stop::Breakpoint(Breakpoint::Cycle(3)));
+        assert_eq!(debugger.cpu.cycle(), 3);
+        assert_eq!(debugger.cpu.x(), 1);
+
+        assert_eq!(
+            debugger.resume(),
+            Stop::Breakpoint(Breakpoint::Instruction(3))
+        );
+        assert_eq!(debugger.cpu.cycle(), 6);
+        assert_eq!(debugger.cpu.x(), -1);
+
+        assert_eq!(debugger.resume(), Stop::Halted);
+    }
+
+    #[test]
+    pub fn test_watches_and_stepping() {
+        let mut debugger = Debugger::new(parse_program(PROGRAM));
+        debugger.execute("watch x < 0");
+
+        assert_eq!(debugger.step_instruction(), Stop::Stepped);
+        assert_eq!(debugger.step_instruction(), Stop::Stepped);
+        assert_eq!(debugger.cpu.cycle(), 4);
+        assert_eq!(debugger.cpu.x(), 4);
+
+        let watch = Watch::parse("x < 0").unwrap();
+        assert_eq!(debugger.resume(), Stop::Watch(watch, 4, -1));
+
+        let output = debugger.execute("print");
+        assert!(output.starts_with("cycle 6 at 3 [noop] (0/1)\nx=-1 y=0 z=0 w=0\n"));
+        assert!(output.ends_with("beam        ^"));
+    }
+
+    #[test]
+    pub fn test_parse_watch() {
+        assert_eq!(Watch::parse("x").unwrap().condition, Condition::Changed);
+        assert_eq!(
+            Watch::parse("y >= -3").unwrap(),
+            Watch {
+                register: Register::Y,
+                condition: Condition::GreaterOrEqual(-3)
+            }
+        );
+        assert!(Watch::parse("x ~ 3").is_err());
+        assert!(Watch::parse("q").is_err());
+    }
+}
@@ -1,177 +1,67 @@
-trait Ticker {
-    fn tick(&mut self) -> bool;
+use cpu::{parse_program, Cpu, Observer};
+use crt::Crt;
+use ocr::decode;
+
+pub mod asm;
+pub mod cpu;
+pub mod crt;
+pub mod debugger;
+pub mod ocr;
+
+// adds up cycle * X during each of the sample cycles
+pub struct SignalStrength {
+    pub samples: Vec<usize>,
+    pub sum: i32,
 }
 
-pub struct Noop {
-    pub counter: i32,
-}
-
-impl Noop {
-    pub fn new() -> Noop {
-        Noop { counter: 1 }
-    }
-}
-
-impl Ticker for Noop {
-    fn tick(&mut self) -> bool {
-        self.counter -= 1;
-        self.counter == 0
+impl Default for SignalStrength {
+    // cycle 20 and every 40 cycles after that up to 220
+    fn default() -> Self {
+        SignalStrength::periodic(20, 40, 220)
     }
 }
 
-pub struct AddX {
-    pub counter: i32,
-    pub x: i32,
-}
-
-impl AddX {
-    pub fn new(x: i32) -> AddX {
-        AddX { counter: 2, x }
+impl SignalStrength {
+    pub fn new(samples: Vec<usize>) -> Self {
+        SignalStrength { samples, sum: 0 }
     }
 
-    pub fn run(&mut self, x: &mut i32) {
-        *x += self.x;
+    pub fn periodic(first: usize, interval: usize, last: usize) -> Self {
+        SignalStrength::new((first..=last).step_by(interval.max(1)).collect())
     }
 }
 
-impl Ticker for AddX {
-    fn tick(&mut self) -> bool {
-        self.counter -= 1;
-        self.counter == 0
+impl Observer for SignalStrength {
+    fn during(&mut self, cpu: &Cpu) {
+        if self.samples.contains(&cpu.cycle()) {
+            self.sum = self
+                .sum
+                .wrapping_add((cpu.cycle() as i32).wrapping_mul(cpu.x()));
+        }
     }
 }
 
-pub enum Command {
-    Noop(Noop),
-    AddX(AddX),
-}
-
 pub fn process_part1(input: &str) -> String {
-    let mut command_list: Vec<Command> = input
-        .lines()
-        .map(|l| {
-            let mut parts = l.split_whitespace();
-            let command = parts.next().unwrap();
-            match command {
-                "noop" => Command::Noop(Noop::new()),
-                "addx" => {
-                    let value = parts.next().unwrap().parse::<i32>().unwrap();
-                    Command::AddX(AddX::new(value))
-                }
-                _ => panic!("Unknown command"),
-            }
-        })
-        .collect();
+    let mut cpu = Cpu::new(parse_program(input));
+    let mut signal = SignalStrength::default();
 
-    let mut x = 1;
-    let mut index = 0;
-
-    let mut results = Vec::new();
-    loop {
-        results.push(x.clone());
-        let command = &mut command_list[index];
-        match command {
-            Command::Noop(noop) => {
-                if noop.tick() {
-                    index += 1;
-                }
-            }
-            Command::AddX(addx) => {
-                if addx.tick() {
-                    addx.run(&mut x);
-                    index += 1;
-                }
-            }
-        }
+    cpu.run(&mut [&mut signal]);
 
-        if index >= command_list.len() {
-            break;
-        }
-    }
+    signal.sum.to_string()
+}
 
-    let twentieth = results[19] * 20;
-    let sixtieth = results[59] * 60;
-    let hundredth = results[99] * 100;
-    let hundred_fortieth = results[139] * 140;
-    let hundred_eightieth = results[179] * 180;
-    let two_hundred_twentieth = results[219] * 220;
+// what the crt shows, to be read by eye
+pub fn process_part2_picture(input: &str) -> String {
+    let mut cpu = Cpu::new(parse_program(input));
+    let mut crt = Crt::default();
 
-    // add all
-    let sum = twentieth
-        + sixtieth
-        + hundredth
-        + hundred_fortieth
-        + hundred_eightieth
-        + two_hundred_twentieth;
+    cpu.run(&mut [&mut crt]);
 
-    sum.to_string()
+    crt.render()
 }
 
 pub fn process_part2(input: &str) -> String {
-    let mut command_list: Vec<Command> = input
-        .lines()
-        .map(|l| {
-            let mut parts = l.split_whitespace();
-            let command = parts.next().unwrap();
-            match command {
-                "noop" => Command::Noop(Noop::new()),
-                "addx" => {
-                    let value = parts.next().unwrap().parse::<i32>().unwrap();
-                    Command::AddX(AddX::new(value))
-                }
-                _ => panic!("Unknown command"),
-            }
-        })
-        .collect();
-
-    let mut x = 1;
-    let mut index = 0;
-
-    let mut results = Vec::new();
-    loop {
-        results.push(x.clone());
-        let command = &mut command_list[index];
-        match command {
-            Command::Noop(noop) => {
-                if noop.tick() {
-                    index += 1;
-                }
-            }
-            Command::AddX(addx) => {
-                if addx.tick() {
-                    addx.run(&mut x);
-                    index += 1;
-                }
-            }
-        }
-
-        if index >= command_list.len() {
-            break;
-        }
-    }
-
-    // "borrowed" from https://github.com/scristobal/advent-of-code/blob/057e6da8cc90ed382c978f63f5512dc08c0a8abd/day-10/src/lib.rs
-    let display = std::iter::zip(0..240, results)
-        .map(|(pix, cmd)| (pix % 40, cmd))
-        .map(|(pix, x)| {
-            if x - 1 <= pix && pix <= x + 1 {
-                '#'
-            } else {
-                '.'
-            }
-        })
-        .collect::<Vec<char>>();
-
-    // collect display in a string, 40 chars per line
-    let mut display_string = String::new();
-    for i in 0..240 {
-        display_string.push(display[i]);
-        if i % 40 == 39 {
-            display_string.push('\n');
-        }
-    }
-
-    display_string.trim().to_string()
+    decode(&process_part2_picture(input)).unwrap_or_else(|e| panic!("{}", e))
 }
 
 #[cfg(test)]
@@ -340,6 +230,18 @@ noop";
 ######......######......######......####
 #######.......#######.......#######.....";
 
-        assert_eq!(process_part2(INPUT), result);
+        assert_eq!(process_part2_picture(INPUT), result);
+    }
+
+    #[test]
+    pub fn test_shared_run() {
+        let mut cpu = cpu::Cpu::new(cpu::parse_program(INPUT));
+        let mut signal = SignalStrength::default();
+        let mut crt = crt::Crt::default();
+
+        cpu.run(&mut [&mut signal, &mut crt]);
+
+        assert_eq!(signal.sum, 13140);
+        assert_eq!(crt.render(), process_part2_picture(INPUT));
     }
 }
@@ -0,0 +1,183 @@
+use std::fmt::{Display, Formatter};
+
+// the width of most glyphs, also used to cut out ones that aren't in the font
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+// every glyph is followed by one blank column
+const SPACING: usize = 1;
+
+// the letters the puzzles are known to use, 6 high and mostly 4 wide
+const FONT: [(char, [&str; GLYPH_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    // the picture isn't 6 rows of equal width
+    BadPicture(String),
+    // glyphs that aren't in the font, with their position (from 0) and how they look
+    UnknownGlyphs(Vec<(usize, Vec<String>)>),
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::BadPicture(reason) => write!(f, "cannot read picture: {}", reason),
+            OcrError::UnknownGlyphs(glyphs) => {
+                write!(f, "{} unknown glyph(s)", glyphs.len())?;
+                for (index, rows) in glyphs {
+                    write!(f, "\n\nletter {}:\n{}", index + 1, rows.join("\n"))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+fn column_is_blank(rows: &[Vec<char>], column: usize) -> bool {
+    rows.iter().all(|r| r.get(column).is_none_or(|&c| c != '#'))
+}
+
+// the widest glyph drawn at `start` that is followed by a blank column
+fn recognise(rows: &[Vec<char>], start: usize) -> Option<(char, usize)> {
+    FONT.iter()
+        .filter(|(_, glyph)| {
+            let end = start + glyph[0].len();
+            rows.iter()
+                .zip(glyph)
+                .all(|(r, g)| end <= r.len() && r[start..end].iter().copied().eq(g.chars()))
+                && column_is_blank(rows, end)
+        })
+        .map(|(letter, glyph)| (*letter, glyph[0].len()))
+        .max_by_key(|&(_, width)| width)
+}
+
+// reads the capital letters out of a picture of # and . rows
+pub fn decode(picture: &str) -> Result<String, OcrError> {
+    let rows = picture
+        .lines()
+        .map(|l| l.trim_end().chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    if rows.len() != GLYPH_HEIGHT {
+        return Err(OcrError::BadPicture(format!(
+            "expected {} rows, found {}",
+            GLYPH_HEIGHT,
+            rows.len()
+        )));
+    }
+
+    let width = rows[0].len();
+    if rows.iter().any(|r| r.len() != width) {
+        return Err(OcrError::BadPicture("rows differ in width".to_string()));
+    }
+
+    let mut letters = String::new();
+    let mut unknown = vec![];
+    let mut start = 0;
+    let mut index = 0;
+
+    // glyphs differ in width, so each one is found where the previous ended
+    while (start..width).any(|c| !column_is_blank(&rows, c)) {
+        let glyph_width = match recognise(&rows, start) {
+            Some((letter, glyph_width)) => {
+                letters.push(letter);
+                glyph_width
+            }
+            None => {
+                let glyph = rows
+                    .iter()
+                    .map(|r| {
+                        r[start..(start + GLYPH_WIDTH).min(width)]
+                            .iter()
+                            .collect::<String>()
+                    })
+                    .collect::<Vec<_>>();
+                unknown.push((index, glyph));
+                GLYPH_WIDTH
+            }
+        };
+        start += glyph_width + SPACING;
+        index += 1;
+    }
+
+    if unknown.is_empty() {
+        Ok(letters)
+    } else {
+        Err(OcrError::UnknownGlyphs(unknown))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PICTURE: &str = "###...##..###....##..##..###..#..#.###..
+#..#.#..#.#..#....#.#..#.#..#.#..#.#..#.
+#..#.#..#.#..#....#.#....###..####.#..#.
+###..####.###.....#.#....#..#.#..#.###..
+#....#..#.#....#..#.#..#.#..#.#..#.#....
+#....#..#.#.....##...##..###..#..#.#....";
+
+    #[test]
+    pub fn test_decode() {
+        assert_eq!(decode(PICTURE).unwrap(), "PAPJCBHP");
+    }
+
+    #[test]
+    pub fn test_wide_glyph() {
+        let picture = "###..#...#..##.
+#..#.#...#.#..#
+#..#..#.#..#..#
+###....#...####
+#......#...#..#
+#......#...#..#";
+
+        assert_eq!(decode(picture).unwrap(), "PYA");
+    }
+
+    #[test]
+    pub fn test_font_round_trip() {
+        for (letter, rows) in FONT {
+            assert_eq!(decode(&rows.join("\n")).unwrap(), letter.to_string());
+        }
+    }
+
+    #[test]
+    pub fn test_unknown_glyph() {
+        let picture = PICTURE.replacen("###.", "#.#.", 1);
+
+        assert_eq!(
+            decode(&picture).unwrap_err().to_string(),
+            "1 unknown glyph(s)
+
+letter 1:
+#.#.
+#..#
+#..#
+###.
+#...
+#..."
+        );
+        assert!(matches!(decode("#..#\n#..#"), Err(OcrError::BadPicture(_))));
+    }
+}
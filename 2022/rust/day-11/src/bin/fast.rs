@@ -0,0 +1,18 @@
+use day_11::{cycles, MonkeyPit};
+use std::{env, fs};
+
+// skips ahead by following each item until its path repeats
+// cargo run --bin fast -- [rounds]
+fn main() {
+    let rounds = env::args()
+        .nth(1)
+        .map_or(1_000_000_000, |a| a.parse().expect("Not a number"));
+
+    let file = fs::read_to_string("./input.txt").expect("Unable to read file");
+    let pit: MonkeyPit = MonkeyPit::parse(&file);
+
+    for (id, count) in cycles::inspections(&pit, rounds) {
+        println!("Monkey {}: {}", id, count);
+    }
+    println!("Monkey business: {}", cycles::monkey_business(&pit, rounds));
+}
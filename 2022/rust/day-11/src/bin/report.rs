@@ -0,0 +1,25 @@
+use day_11::{relief, MonkeyPit};
+use std::{env, fs};
+
+// runs the monkeys and prints inspections per monkey over time
+// cargo run --bin report -- [rounds] [modular|none|divide:N] [window]
+fn main() {
+    let mut args = env::args().skip(1);
+    let rounds = args
+        .next()
+        .map_or(10000, |a| a.parse().expect("Not a number"));
+    let name = args.next().unwrap_or_else(|| "modular".to_string());
+    let window = args
+        .next()
+        .map_or(1000, |a| a.parse().expect("Not a number"));
+
+    let relief = relief::from_name(&name).expect("Unknown relief policy");
+
+    let file = fs::read_to_string("./input.txt").expect("Unable to read file");
+    let mut pit: MonkeyPit = MonkeyPit::parse(&file);
+    pit.run(rounds, relief.as_ref());
+
+    println!("Relief: {}", relief.name());
+    print!("{}", pit.throughput(window));
+    println!("Monkey business: {}", pit.monkey_business());
+}
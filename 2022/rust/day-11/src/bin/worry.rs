@@ -0,0 +1,25 @@
+use day_11::{process_with_backend, worry::Backend};
+use std::{env, fs};
+
+// runs the monkeys on a chosen number backend, e.g. to check the modular trick
+// cargo run --bin worry -- big [rounds] [modular|none|divide:N]
+fn main() {
+    let mut args = env::args().skip(1);
+    let backend: Backend = args
+        .next()
+        .unwrap_or_else(|| "modular".to_string())
+        .parse()
+        .expect("Unknown backend");
+    let rounds = args
+        .next()
+        .map_or(10000, |a| a.parse().expect("Not a number"));
+    let relief = args.next().unwrap_or_else(|| "modular".to_string());
+
+    let file = fs::read_to_string("./input.txt").expect("Unable to read file");
+
+    println!(
+        "{:?}: {}",
+        backend,
+        process_with_backend(&file, backend, rounds, &relief)
+    );
+}
@@ -0,0 +1,95 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::MonkeyPit;
+
+// items never interact, so each one can be followed on its own. with worry
+// kept modulo the divisors an item's (monkey, worry) state space is finite and
+// its path eventually repeats, after which the inspections can be counted
+// without simulating the remaining rounds
+pub fn inspections(pit: &MonkeyPit, rounds: u64) -> BTreeMap<i64, u64> {
+    if let Some(id) = pit.first_dividing() {
+        panic!(
+            "Monkey {} divides worry, so its items can't be followed modulo the divisors",
+            id
+        );
+    }
+
+    let mut counts: BTreeMap<i64, u64> = pit.monkeys.keys().map(|&id| (id, 0)).collect();
+
+    for (&start, monkey) in &pit.monkeys {
+        for &item in &monkey.items {
+            for (id, count) in follow(pit, start, item, rounds) {
+                *counts.get_mut(&id).unwrap() += count;
+            }
+        }
+    }
+
+    counts
+}
+
+// inspections made by every monkey on one item within the first `rounds`
+// rounds, starting with the item held by `start` before round one
+fn follow(pit: &MonkeyPit, start: i64, worry: i64, rounds: u64) -> BTreeMap<i64, u64> {
+    let mut counts: BTreeMap<i64, u64> = BTreeMap::new();
+    // (round, monkey) of every inspection so far
+    let mut path: Vec<(u64, i64)> = Vec::new();
+    let mut seen: HashMap<(i64, i64), usize> = HashMap::new();
+
+    let (mut round, mut holder, mut worry) = (0, start, worry);
+    while round < rounds {
+        if let Some(&first) = seen.get(&(holder, worry)) {
+            // everything from `first` on repeats every `period` rounds
+            let period = round - path[first].0;
+            for &(r, id) in &path[first..] {
+                *counts.entry(id).or_default() += (rounds - 1 - r) / period;
+            }
+            break;
+        }
+        seen.insert((holder, worry), path.len());
+        path.push((round, holder));
+        *counts.entry(holder).or_default() += 1;
+
+        let monkey = &pit.monkeys[&holder];
+        worry = monkey.operation.eval(&worry).rem_euclid(pit.modulus);
+        let to = (monkey.test)(&worry);
+        // monkeys take turns in id order, a lower id only gets it next round
+        if to <= holder {
+            round += 1;
+        }
+        holder = to;
+    }
+
+    counts
+}
+
+// product of the two highest inspection counts, which overflows u64 for very
+// long runs
+pub fn monkey_business(pit: &MonkeyPit, rounds: u64) -> u128 {
+    let mut counts: Vec<u64> = inspections(pit, rounds).into_values().collect();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    counts.iter().take(2).map(|&c| c as u128).product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::relief::ReduceModulo;
+    use crate::tests::INPUT;
+
+    #[test]
+    pub fn test_matches_simulation() {
+        for rounds in [1, 20, 1000, 10000] {
+            let mut pit: MonkeyPit = MonkeyPit::parse(INPUT);
+            let fast = inspections(&pit, rounds);
+            pit.run(rounds as usize, &ReduceModulo);
+            assert_eq!(fast, pit.inspections());
+        }
+    }
+
+    #[test]
+    pub fn test_billion_rounds() {
+        let pit: MonkeyPit = MonkeyPit::parse(INPUT);
+        assert_eq!(monkey_business(&pit, 10000), 2713310158);
+        assert!(monkey_business(&pit, 1_000_000_000) > 2713310158);
+    }
+}
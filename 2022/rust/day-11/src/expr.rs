@@ -0,0 +1,340 @@
+use std::fmt;
+
+use crate::worry::Worry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinOp {
+    fn from_char(c: char) -> Option<BinOp> {
+        match c {
+            '+' => Some(BinOp::Add),
+            '-' => Some(BinOp::Sub),
+            '*' => Some(BinOp::Mul),
+            '/' => Some(BinOp::Div),
+            '%' => Some(BinOp::Rem),
+            _ => None,
+        }
+    }
+
+    fn precedence(self) -> u8 {
+        match self {
+            BinOp::Add | BinOp::Sub => 1,
+            BinOp::Mul | BinOp::Div | BinOp::Rem => 2,
+        }
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = match self {
+            BinOp::Add => '+',
+            BinOp::Sub => '-',
+            BinOp::Mul => '*',
+            BinOp::Div => '/',
+            BinOp::Rem => '%',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Old,
+    Num(i64),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn eval<W: Worry>(&self, old: &W) -> W {
+        match self {
+            Expr::Old => old.clone(),
+            Expr::Num(n) => W::from_i64(*n),
+            Expr::Neg(e) => e.eval(old).negate(),
+            Expr::Binary(op, l, r) => l.eval(old).apply(*op, &r.eval(old)),
+        }
+    }
+
+    pub fn compile(&self) -> Program {
+        let mut ops = Vec::new();
+        self.emit(&mut ops);
+        Program { ops }
+    }
+
+    fn emit(&self, ops: &mut Vec<Op>) {
+        match self {
+            Expr::Old => ops.push(Op::Old),
+            Expr::Num(n) => ops.push(Op::Push(*n)),
+            Expr::Neg(e) => {
+                e.emit(ops);
+                ops.push(Op::Neg);
+            }
+            Expr::Binary(op, l, r) => {
+                l.emit(ops);
+                r.emit(ops);
+                ops.push(Op::Bin(*op));
+            }
+        }
+    }
+}
+
+// prints fully parenthesised so the output always parses back to the same tree
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Old => write!(f, "old"),
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Neg(e) => write!(f, "-{}", e),
+            Expr::Binary(op, l, r) => write!(f, "({} {} {})", l, op, r),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Old,
+    Push(i64),
+    Neg,
+    Bin(BinOp),
+}
+
+// postfix bytecode for a stack machine, cheaper to evaluate than walking the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub ops: Vec<Op>,
+}
+
+impl Program {
+    // taking worry modulo the divisors only preserves +, - and *
+    pub fn divides(&self) -> bool {
+        self.ops
+            .iter()
+            .any(|op| matches!(op, Op::Bin(BinOp::Div | BinOp::Rem)))
+    }
+
+    pub fn eval<W: Worry>(&self, old: &W) -> W {
+        let mut stack: Vec<W> = Vec::with_capacity(self.ops.len());
+        for op in &self.ops {
+            match *op {
+                Op::Old => stack.push(old.clone()),
+                Op::Push(n) => stack.push(W::from_i64(n)),
+                Op::Neg => {
+                    let v = stack.pop().expect("stack underflow");
+                    stack.push(v.negate());
+                }
+                Op::Bin(op) => {
+                    let r = stack.pop().expect("stack underflow");
+                    let l = stack.pop().expect("stack underflow");
+                    stack.push(l.apply(op, &r));
+                }
+            }
+        }
+        stack.pop().expect("empty program")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Old,
+    Num(i64),
+    Op(char),
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().collect();
+            let n = digits.parse().map_err(|_| ExprError {
+                column,
+                message: format!("number {} is too large", digits),
+            })?;
+            tokens.push((column, Token::Num(n)));
+        } else if c.is_ascii_alphabetic() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if word != "old" {
+                return Err(ExprError {
+                    column,
+                    message: format!("unknown variable {}", word),
+                });
+            }
+            tokens.push((column, Token::Old));
+        } else {
+            let token = match c {
+                '(' => Token::Open,
+                ')' => Token::Close,
+                c if BinOp::from_char(c).is_some() => Token::Op(c),
+                c => {
+                    return Err(ExprError {
+                        column,
+                        message: format!("unexpected character {:?}", c),
+                    })
+                }
+            };
+            tokens.push((column, token));
+            i += 1;
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).map(|&(_, t)| t)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |&(c, _)| c)
+    }
+
+    fn error(&self, message: &str) -> ExprError {
+        ExprError {
+            column: self.column(),
+            message: message.to_string(),
+        }
+    }
+
+    // precedence climbing, all binary operators are left associative
+    fn expr(&mut self, min_prec: u8) -> Result<Expr, ExprError> {
+        let mut left = self.unary()?;
+        while let Some(Token::Op(c)) = self.peek() {
+            let op = BinOp::from_char(c).unwrap();
+            if op.precedence() < min_prec {
+                break;
+            }
+            self.pos += 1;
+            let right = self.expr(op.precedence() + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ExprError> {
+        if self.peek() == Some(Token::Op('-')) {
+            self.pos += 1;
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, ExprError> {
+        let expr = match self.peek() {
+            Some(Token::Old) => Expr::Old,
+            Some(Token::Num(n)) => Expr::Num(n),
+            Some(Token::Open) => {
+                self.pos += 1;
+                let inner = self.expr(0)?;
+                if self.peek() != Some(Token::Close) {
+                    return Err(self.error("expected )"));
+                }
+                inner
+            }
+            Some(_) => return Err(self.error("expected old, a number or (")),
+            None => return Err(self.error("unexpected end of expression")),
+        };
+        self.pos += 1;
+        Ok(expr)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Expr, ExprError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: input.chars().count() + 1,
+    };
+    let expr = parser.expr(0)?;
+    if parser.peek().is_some() {
+        return Err(parser.error("unexpected trailing input"));
+    }
+    Ok(expr)
+}
+
+pub fn compile(input: &str) -> Result<Program, ExprError> {
+    parse(input).map(|e| e.compile())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_precedence() {
+        let program = compile("old + 2 * 3").unwrap();
+        assert_eq!(program.eval(&1i64), 7);
+        let program = compile("(old + 2) * 3").unwrap();
+        assert_eq!(program.eval(&1i64), 9);
+        let program = compile("old - 10 - 3").unwrap();
+        assert_eq!(program.eval(&20i64), 7);
+    }
+
+    #[test]
+    pub fn test_operators() {
+        let program = compile("old * old / 7 % 5 - -old").unwrap();
+        assert_eq!(program.eval(&10i64), 100 / 7 % 5 + 10);
+        assert_eq!(parse("old * old").unwrap().eval(&9i64), 81);
+        assert!(program.divides());
+        assert!(!compile("-(old + 3) * old").unwrap().divides());
+    }
+
+    #[test]
+    pub fn test_display_round_trip() {
+        let expr = parse("old * (old + 3) % 7").unwrap();
+        assert_eq!(expr.to_string(), "((old * (old + 3)) % 7)");
+        assert_eq!(parse(&expr.to_string()).unwrap(), expr);
+    }
+
+    #[test]
+    pub fn test_errors() {
+        assert_eq!(parse("old +").unwrap_err().column, 6);
+        assert_eq!(parse("(old * 2").unwrap_err().message, "expected )");
+        assert_eq!(parse("new + 1").unwrap_err().column, 1);
+        assert_eq!(parse("old ^ 2").unwrap_err().column, 5);
+    }
+}
@@ -3,52 +3,61 @@ use std::{
     fmt::Formatter,
 };
 
+use expr::Program;
 use nom::IResult;
+use num_bigint::BigInt;
+use relief::Relief;
+use worry::{Backend, Worry};
 
-pub struct Monkey<'a> {
-    pub items: VecDeque<i64>,
-    pub inspected_items: i64,
-    inspect: Box<dyn Fn(i64) -> i64 + 'a>,
+pub mod cycles;
+pub mod expr;
+pub mod pit;
+pub mod relief;
+pub mod worry;
+
+pub use pit::MonkeyPit;
+
+pub type Test<W> = Box<dyn Fn(&W) -> i64>;
+
+pub struct Monkey<W = i64> {
+    pub items: VecDeque<W>,
+    pub inspected_items: u64,
+    pub operation: Program,
     pub diviser: i64,
-    test: Box<dyn Fn(i64) -> i64 + 'a>,
+    test: Test<W>,
 }
 
-impl<'a> Monkey<'a> {
-    pub fn inspect(&mut self, prime: i64) {
+impl<W: Worry> Monkey<W> {
+    pub fn inspect(&mut self) {
         let item = self.items.pop_front();
         if let Some(item) = item {
             self.inspected_items += 1;
-            let new_item = (self.inspect)(item);
-            self.items.push_front(new_item % prime);
+            let new_item = self.operation.eval(&item);
+            self.items.push_front(new_item);
         }
     }
 
-    pub fn bored(&mut self) {
+    pub fn relieve(&mut self, relief: &dyn Relief<W>, modulus: i64) {
         let item = self.items.pop_front();
 
         if let Some(item) = item {
-            let new_item = (item as f64 / 3.0).floor() as i64;
-            self.items.push_front(new_item);
+            self.items.push_front(relief.relieve(&item, modulus));
         }
     }
 
-    pub fn throw_to(&mut self) -> Option<(i64, i64)> {
+    pub fn throw_to(&mut self) -> Option<(W, i64)> {
         let item = self.items.pop_front();
 
         if let Some(item) = item {
-            let new_id = (self.test)(item);
-            return Some((item.clone(), new_id));
+            let new_id = (self.test)(&item);
+            return Some((item, new_id));
         }
 
         None
     }
 }
 
-pub struct MonkeyPit<'a> {
-    pub monkeys: BTreeMap<i64, Monkey<'a>>,
-}
-
-impl<'a> std::fmt::Debug for Monkey<'a> {
+impl<W: Worry> std::fmt::Debug for Monkey<W> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Monkey")
             .field("items", &self.items)
@@ -64,54 +73,32 @@ pub fn parse_id(input: &str) -> IResult<&str, i64> {
     Ok((input, id))
 }
 
-pub fn parse_items(input: &str) -> IResult<&str, VecDeque<i64>> {
+pub fn parse_items<W: Worry>(input: &str) -> IResult<&str, VecDeque<W>> {
     let (input, _) = nom::character::complete::multispace0(input)?;
     let (input, _) = nom::bytes::complete::tag("Starting items: ")(input)?;
     let (input, items) = nom::multi::separated_list1(
         nom::bytes::complete::tag(", "),
         nom::character::complete::i64,
     )(input)?;
-    let items = items.into_iter().collect();
+    let items = items.into_iter().map(W::from_i64).collect();
     let (input, _) = nom::character::complete::newline(input)?;
 
     Ok((input, items))
 }
 
-fn operate<'a>(i: i64, left: &'a str, operation: &'a str, right: &'a str) -> i64 {
-    let left = if left == "old" {
-        i
-    } else {
-        left.parse().unwrap()
-    };
-    let right = if right == "old" {
-        i
-    } else {
-        (*right.clone()).parse().unwrap()
-    };
-
-    match operation {
-        "+" => left + right,
-        "*" => left * right,
-        _ => panic!("Unknown operator: {}", operation),
-    }
-}
-
-pub fn parse_operation<'a>(input: &'a str) -> IResult<&str, Box<dyn Fn(i64) -> i64 + 'a>> {
+// a malformed expression still parses as a line, the error describes it
+// so the caller can say which monkey it belongs to
+pub fn parse_operation(input: &str) -> IResult<&str, Result<Program, String>> {
     let (input, _) = nom::character::complete::multispace0(input)?;
     let (input, _) = nom::bytes::complete::tag("Operation: new = ")(input)?;
     let (input, op_as_str) = nom::bytes::complete::is_not("\n")(input)?;
 
-    let operation: Vec<&str> = op_as_str.split_whitespace().collect();
-
-    let operation = match operation.as_slice() {
-        [left, operator, right] => Box::new(|i| operate(i, left, operator, right)),
-        _ => panic!("Unknown operation: {:?}", operation),
-    };
+    let program = expr::compile(op_as_str).map_err(|e| format!("`{}`, {}", op_as_str, e));
 
-    Ok((input, operation))
+    Ok((input, program))
 }
 
-pub fn parse_test(input: &str) -> IResult<&str, (i64, Box<dyn Fn(i64) -> i64>)> {
+pub fn parse_test<W: Worry + 'static>(input: &str) -> IResult<&str, (i64, Test<W>)> {
     let (input, _) = nom::character::complete::multispace0(input)?;
     let (input, _) = nom::bytes::complete::tag("Test: divisible by ")(input)?;
     let (input, test) = nom::character::complete::i64(input)?;
@@ -130,16 +117,23 @@ pub fn parse_test(input: &str) -> IResult<&str, (i64, Box<dyn Fn(i64) -> i64>)>
     Ok((
         input,
         (
-            test.clone(),
-            Box::new(move |i| if i % test == 0 { true_id } else { false_id }),
+            test,
+            Box::new(move |i: &W| {
+                if i.is_divisible_by(test) {
+                    true_id
+                } else {
+                    false_id
+                }
+            }),
         ),
     ))
 }
 
-pub fn parse_monkey<'a>(input: &'a str) -> IResult<&'a str, (i64, Monkey)> {
+pub fn parse_monkey<W: Worry + 'static>(input: &str) -> IResult<&str, (i64, Monkey<W>)> {
     let (input, id) = parse_id(input)?;
     let (input, items) = parse_items(input)?;
-    let (input, inspect) = parse_operation(input)?;
+    let (input, operation) = parse_operation(input)?;
+    let operation = operation.unwrap_or_else(|e| panic!("Monkey {}: invalid operation {}", id, e));
     let (input, test) = parse_test(input)?;
 
     // remove any optional newlines
@@ -155,7 +149,7 @@ pub fn parse_monkey<'a>(input: &'a str) -> IResult<&'a str, (i64, Monkey)> {
             Monkey {
                 items,
                 inspected_items: 0,
-                inspect,
+                operation,
                 diviser,
                 test,
             },
@@ -163,110 +157,38 @@ pub fn parse_monkey<'a>(input: &'a str) -> IResult<&'a str, (i64, Monkey)> {
     ))
 }
 
-pub fn process_part1<'a>(input: &'a str) -> String {
-    let monkeys = match nom::multi::many1(parse_monkey)(input) {
-        Ok((_, monkeys)) => monkeys,
+pub fn parse_monkeys<W: Worry + 'static>(input: &str) -> BTreeMap<i64, Monkey<W>> {
+    match nom::multi::many1(parse_monkey::<W>)(input) {
+        Ok((_, monkeys)) => monkeys.into_iter().collect(),
         Err(e) => panic!("Error parsing input: {:?}", e),
-    };
-
-    let mut monkeys: BTreeMap<i64, Monkey> = monkeys.into_iter().collect();
-
-    const MAX_ROUNDS: usize = 20;
-    let mut round: usize = 0;
-    let mut monkey_turn_id: i64 = 0;
-
-    let prime = monkeys.iter().map(|(_, monkey)| monkey.diviser).product();
-
-    while round < MAX_ROUNDS {
-        let mut receivers: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
-
-        let monkey = monkeys.get_mut(&monkey_turn_id).unwrap();
-        while monkey.items.len() != 0 {
-            monkey.inspect(prime);
-            monkey.bored();
-            if let Some((item, monkey_id)) = monkey.throw_to() {
-                receivers.entry(monkey_id).or_insert(vec![]).push(item);
-            }
-        }
-
-        for (monkey_id, items) in receivers {
-            let monkey = monkeys.get_mut(&monkey_id).unwrap();
-            for item in items {
-                monkey.items.push_back(item);
-            }
-        }
-
-        monkey_turn_id += 1;
-        if monkey_turn_id == monkeys.len() as i64 {
-            monkey_turn_id = 0;
-            round += 1;
-        }
     }
+}
 
-    // grab the 2 monkeys that inspected the most items
-    let mut monkeys: Vec<_> = monkeys.into_iter().collect();
-    monkeys.sort_by(|(_, a), (_, b)| b.inspected_items.cmp(&a.inspected_items));
-
-    let sum: i64 = monkeys
-        .iter()
-        .take(2)
-        .map(|(_, monkey)| monkey.inspected_items)
-        .product();
+fn monkey_business<W: Worry + 'static>(input: &str, rounds: usize, relief: &str) -> u64 {
+    let relief = relief::from_name::<W>(relief)
+        .unwrap_or_else(|| panic!("Unknown relief policy: {}", relief));
 
-    sum.to_string()
+    let mut pit: MonkeyPit<W> = MonkeyPit::parse(input);
+    pit.run(rounds, relief.as_ref());
+    pit.monkey_business()
 }
 
-pub fn process_part2(input: &str) -> String {
-    let monkeys = match nom::multi::many1(parse_monkey)(input) {
-        Ok((_, monkeys)) => monkeys,
-        Err(e) => panic!("Error parsing input: {:?}", e),
+pub fn process_with_backend(input: &str, backend: Backend, rounds: usize, relief: &str) -> String {
+    let business = match backend {
+        Backend::Modular => monkey_business::<i64>(input, rounds, relief),
+        Backend::U128 => monkey_business::<u128>(input, rounds, relief),
+        Backend::Big => monkey_business::<BigInt>(input, rounds, relief),
     };
 
-    let mut monkeys: BTreeMap<i64, Monkey> = monkeys.into_iter().collect();
-
-    let prime = monkeys.iter().map(|(_, monkey)| monkey.diviser).product();
-
-    const MAX_ROUNDS: usize = 10000;
-    let mut round: usize = 0;
-    let mut monkey_turn_id: i64 = 0;
-
-    while round < MAX_ROUNDS {
-        let mut receivers: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
-
-        let monkey = monkeys.get_mut(&monkey_turn_id).unwrap();
-        while monkey.items.len() != 0 {
-            monkey.inspect(prime);
-            // monkey.bored();
-            if let Some((item, monkey_id)) = monkey.throw_to() {
-                receivers.entry(monkey_id).or_insert(vec![]).push(item);
-            }
-        }
-
-        for (monkey_id, items) in receivers {
-            let monkey = monkeys.get_mut(&monkey_id).unwrap();
-            for item in items {
-                monkey.items.push_back(item);
-            }
-        }
-
-        monkey_turn_id += 1;
-        if monkey_turn_id == monkeys.len() as i64 {
-            monkey_turn_id = 0;
-            round += 1;
-        }
-    }
-
-    // grab the 2 monkeys that inspected the most items
-    let mut monkeys: Vec<_> = monkeys.into_iter().collect();
-    monkeys.sort_by(|(_, a), (_, b)| b.inspected_items.cmp(&a.inspected_items));
+    business.to_string()
+}
 
-    let sum: i64 = monkeys
-        .iter()
-        .take(2)
-        .map(|(_, monkey)| monkey.inspected_items)
-        .product();
+pub fn process_part1(input: &str) -> String {
+    process_with_backend(input, Backend::Modular, 20, "divide:3")
+}
 
-    sum.to_string()
+pub fn process_part2(input: &str) -> String {
+    process_with_backend(input, Backend::Modular, 10000, "modular")
 }
 
 #[cfg(test)]
@@ -274,7 +196,7 @@ mod tests {
 
     use crate::*;
 
-    const INPUT: &str = "Monkey 0:
+    pub(crate) const INPUT: &str = "Monkey 0:
   Starting items: 79, 98
   Operation: new = old * 19
   Test: divisible by 23
@@ -311,4 +233,37 @@ Monkey 3:
     pub fn test_part_2() {
         assert_eq!(process_part2(INPUT), "2713310158");
     }
+
+    #[test]
+    #[should_panic(
+        expected = "Monkey 1: invalid operation `old ^ 2`, column 5: unexpected character '^'"
+    )]
+    pub fn test_operation_error() {
+        parse_monkeys::<i64>(&INPUT.replace("old + 6", "old ^ 2"));
+    }
+
+    #[test]
+    pub fn test_backends_reduce() {
+        for backend in [Backend::U128, Backend::Big] {
+            assert_eq!(
+                process_with_backend(INPUT, backend, 10000, "modular"),
+                "2713310158"
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_backends_agree() {
+        for backend in [Backend::Modular, Backend::U128, Backend::Big] {
+            assert_eq!(
+                process_with_backend(INPUT, backend, 20, "divide:3"),
+                "10605"
+            );
+        }
+        // without the modular trick worry grows past u128 quickly
+        assert_eq!(
+            process_with_backend(INPUT, Backend::Big, 20, "none"),
+            process_with_backend(INPUT, Backend::Modular, 20, "modular")
+        );
+    }
 }
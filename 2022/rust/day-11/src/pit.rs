@@ -0,0 +1,210 @@
+use std::{collections::BTreeMap, fmt, ops::RangeInclusive};
+
+use crate::{
+    parse_monkeys,
+    relief::Relief,
+    worry::{self, Worry},
+    Monkey,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoundStats {
+    pub inspections: BTreeMap<i64, u64>,
+    // items thrown, keyed by (from, to)
+    pub transfers: BTreeMap<(i64, i64), u64>,
+}
+
+pub struct MonkeyPit<W = i64> {
+    pub monkeys: BTreeMap<i64, Monkey<W>>,
+    pub modulus: i64,
+    pub history: Vec<RoundStats>,
+}
+
+impl<W: Worry + 'static> MonkeyPit<W> {
+    pub fn new(monkeys: BTreeMap<i64, Monkey<W>>) -> Self {
+        let modulus = worry::lcm(monkeys.values().map(|monkey| monkey.diviser));
+        MonkeyPit {
+            monkeys,
+            modulus,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn parse(input: &str) -> Self {
+        MonkeyPit::new(parse_monkeys(input))
+    }
+
+    // the first monkey whose operation uses / or %, which makes reducing
+    // worry modulo the divisors change the outcome
+    pub fn first_dividing(&self) -> Option<i64> {
+        self.monkeys
+            .iter()
+            .find(|(_, monkey)| monkey.operation.divides())
+            .map(|(&id, _)| id)
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.history.len()
+    }
+
+    // every monkey takes one turn in id order, items thrown during a turn
+    // land at the back of the receiver's queue
+    pub fn play_round(&mut self, relief: &dyn Relief<W>) -> &RoundStats {
+        if let (true, Some(id)) = (relief.reduces(), self.first_dividing()) {
+            panic!(
+                "Monkey {} divides worry, which can't be combined with {}",
+                id,
+                relief.name()
+            );
+        }
+
+        let mut stats = RoundStats::default();
+        let ids: Vec<i64> = self.monkeys.keys().copied().collect();
+
+        for id in ids {
+            let monkey = self.monkeys.get_mut(&id).unwrap();
+            let mut thrown = Vec::new();
+            while !monkey.items.is_empty() {
+                monkey.inspect();
+                monkey.relieve(relief, self.modulus);
+                if let Some(throw) = monkey.throw_to() {
+                    thrown.push(throw);
+                }
+            }
+
+            stats.inspections.insert(id, thrown.len() as u64);
+            for (item, to) in thrown {
+                *stats.transfers.entry((id, to)).or_default() += 1;
+                let receiver = self
+                    .monkeys
+                    .get_mut(&to)
+                    .unwrap_or_else(|| panic!("Monkey {} throws to unknown monkey {}", id, to));
+                receiver.items.push_back(item);
+            }
+        }
+
+        self.history.push(stats);
+        self.history.last().unwrap()
+    }
+
+    pub fn run(&mut self, rounds: usize, relief: &dyn Relief<W>) {
+        for _ in 0..rounds {
+            self.play_round(relief);
+        }
+    }
+}
+
+impl<W> MonkeyPit<W> {
+    pub fn inspections(&self) -> BTreeMap<i64, u64> {
+        self.monkeys
+            .iter()
+            .map(|(&id, monkey)| (id, monkey.inspected_items))
+            .collect()
+    }
+
+    // product of the two highest inspection counts
+    pub fn monkey_business(&self) -> u64 {
+        let mut counts: Vec<u64> = self.inspections().into_values().collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        counts.iter().take(2).product()
+    }
+
+    // inspections per monkey summed over windows of `window` rounds
+    pub fn throughput(&self, window: usize) -> Throughput {
+        let window = window.max(1);
+        let monkeys: Vec<i64> = self.monkeys.keys().copied().collect();
+        let windows = self
+            .history
+            .chunks(window)
+            .enumerate()
+            .map(|(i, rounds)| {
+                let first = i * window + 1;
+                let counts = monkeys
+                    .iter()
+                    .map(|id| rounds.iter().map(|r| r.inspections[id]).sum())
+                    .collect();
+                (first..=first + rounds.len() - 1, counts)
+            })
+            .collect();
+
+        Throughput { monkeys, windows }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throughput {
+    pub monkeys: Vec<i64>,
+    // 1-based round range and inspections per monkey within it
+    pub windows: Vec<(RangeInclusive<usize>, Vec<u64>)>,
+}
+
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>13}", "rounds")?;
+        for id in &self.monkeys {
+            write!(f, " {:>10}", format!("monkey {}", id))?;
+        }
+        writeln!(f)?;
+
+        for (rounds, counts) in &self.windows {
+            write!(f, "{:>13}", format!("{}-{}", rounds.start(), rounds.end()))?;
+            for count in counts {
+                write!(f, " {:>10}", count)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::relief::{DivideBy, ReduceModulo};
+    use crate::tests::INPUT;
+
+    #[test]
+    pub fn test_round_stats() {
+        let mut pit: MonkeyPit = MonkeyPit::parse(INPUT);
+        let stats = pit.play_round(&DivideBy(3)).clone();
+
+        assert_eq!(stats.inspections.values().sum::<u64>(), 2 + 4 + 3 + 5);
+        assert_eq!(stats.transfers[&(0, 3)], 2);
+        assert_eq!(pit.monkeys[&0].items, [20, 23, 27, 26]);
+
+        pit.run(19, &DivideBy(3));
+        assert_eq!(pit.rounds(), 20);
+        assert_eq!(
+            pit.inspections().into_values().collect::<Vec<_>>(),
+            [101, 95, 7, 105]
+        );
+        assert_eq!(pit.monkey_business(), 10605);
+    }
+
+    #[test]
+    #[should_panic(expected = "Monkey 1 divides worry")]
+    pub fn test_modular_refuses_division() {
+        let input = INPUT.replace("old + 6", "(old + 6) / 2");
+        let mut pit: MonkeyPit = MonkeyPit::parse(&input);
+        assert_eq!(pit.first_dividing(), Some(1));
+
+        pit.run(20, &DivideBy(3));
+        pit.run(1, &ReduceModulo);
+    }
+
+    #[test]
+    pub fn test_throughput() {
+        let mut pit: MonkeyPit = MonkeyPit::parse(INPUT);
+        pit.run(1000, &ReduceModulo);
+
+        let throughput = pit.throughput(400);
+        assert_eq!(throughput.windows.len(), 3);
+        assert_eq!(throughput.windows[2].0, 801..=1000);
+
+        let totals: Vec<u64> = (0..4)
+            .map(|i| throughput.windows.iter().map(|(_, c)| c[i]).sum())
+            .collect();
+        assert_eq!(totals, [5204, 4792, 199, 5192]);
+    }
+}
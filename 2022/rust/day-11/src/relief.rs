@@ -0,0 +1,72 @@
+use crate::worry::Worry;
+
+pub trait Relief<W: Worry> {
+    fn name(&self) -> String;
+
+    // worry after the monkey gets bored with an item, `modulus` is the lcm of
+    // every monkey's divisor
+    fn relieve(&self, worry: &W, modulus: i64) -> W;
+
+    // whether this takes worry modulo the divisors
+    fn reduces(&self) -> bool {
+        false
+    }
+}
+
+// the part 1 rule, worry is divided and rounded down
+pub struct DivideBy(pub i64);
+
+impl<W: Worry> Relief<W> for DivideBy {
+    fn name(&self) -> String {
+        format!("divide by {}", self.0)
+    }
+
+    fn relieve(&self, worry: &W, _modulus: i64) -> W {
+        worry.relieve(self.0)
+    }
+}
+
+// no relief, but keeps numbers small without changing any test outcome
+pub struct ReduceModulo;
+
+impl<W: Worry> Relief<W> for ReduceModulo {
+    fn name(&self) -> String {
+        "reduce modulo divisors".to_string()
+    }
+
+    fn relieve(&self, worry: &W, modulus: i64) -> W {
+        worry.reduce(modulus)
+    }
+
+    fn reduces(&self) -> bool {
+        true
+    }
+}
+
+// no relief at all, only usable with a big integer backend for long runs
+pub struct NoRelief;
+
+impl<W: Worry> Relief<W> for NoRelief {
+    fn name(&self) -> String {
+        "none".to_string()
+    }
+
+    fn relieve(&self, worry: &W, _modulus: i64) -> W {
+        worry.clone()
+    }
+}
+
+pub fn from_name<W: Worry>(name: &str) -> Option<Box<dyn Relief<W>>> {
+    match name {
+        "modular" => Some(Box::new(ReduceModulo)),
+        "none" => Some(Box::new(NoRelief)),
+        _ => {
+            let factor = name
+                .strip_prefix("divide:")?
+                .parse()
+                .ok()
+                .filter(|&f: &i64| f > 0)?;
+            Some(Box::new(DivideBy(factor)))
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::{fmt, str::FromStr};
+
+use num_bigint::BigInt;
+
+use crate::expr::BinOp;
+
+// a worry level. `reduce` takes it modulo the lcm of the divisors, which keeps
+// every divisibility test exact as long as operations only use +, - and *;
+// the pit refuses to reduce when any operation divides
+pub trait Worry: Clone + fmt::Debug + fmt::Display {
+    fn from_i64(n: i64) -> Self;
+    fn apply(&self, op: BinOp, rhs: &Self) -> Self;
+    fn negate(&self) -> Self;
+    fn is_divisible_by(&self, divisor: i64) -> bool;
+    fn relieve(&self, factor: i64) -> Self;
+    fn reduce(&self, modulus: i64) -> Self;
+}
+
+fn check_divisor(op: BinOp, is_zero: bool) {
+    if is_zero && matches!(op, BinOp::Div | BinOp::Rem) {
+        panic!("worry divided by zero");
+    }
+}
+
+impl Worry for i64 {
+    fn from_i64(n: i64) -> Self {
+        n
+    }
+
+    fn apply(&self, op: BinOp, rhs: &Self) -> Self {
+        check_divisor(op, *rhs == 0);
+        let result = match op {
+            BinOp::Add => self.checked_add(*rhs),
+            BinOp::Sub => self.checked_sub(*rhs),
+            BinOp::Mul => self.checked_mul(*rhs),
+            BinOp::Div => self.checked_div(*rhs),
+            BinOp::Rem => self.checked_rem(*rhs),
+        };
+        result.unwrap_or_else(|| panic!("worry overflow: {} {} {}", self, op, rhs))
+    }
+
+    fn negate(&self) -> Self {
+        -self
+    }
+
+    fn is_divisible_by(&self, divisor: i64) -> bool {
+        self % divisor == 0
+    }
+
+    fn relieve(&self, factor: i64) -> Self {
+        self.div_euclid(factor)
+    }
+
+    fn reduce(&self, modulus: i64) -> Self {
+        self.rem_euclid(modulus)
+    }
+}
+
+impl Worry for u128 {
+    fn from_i64(n: i64) -> Self {
+        u128::try_from(n).unwrap_or_else(|_| panic!("negative worry: {}", n))
+    }
+
+    fn apply(&self, op: BinOp, rhs: &Self) -> Self {
+        check_divisor(op, *rhs == 0);
+        let result = match op {
+            BinOp::Add => self.checked_add(*rhs),
+            BinOp::Sub => self.checked_sub(*rhs),
+            BinOp::Mul => self.checked_mul(*rhs),
+            BinOp::Div => self.checked_div(*rhs),
+            BinOp::Rem => self.checked_rem(*rhs),
+        };
+        result.unwrap_or_else(|| panic!("worry overflow: {} {} {}", self, op, rhs))
+    }
+
+    fn negate(&self) -> Self {
+        match self {
+            0 => 0,
+            _ => panic!("negative worry: -{}", self),
+        }
+    }
+
+    fn is_divisible_by(&self, divisor: i64) -> bool {
+        self.is_multiple_of(Self::from_i64(divisor))
+    }
+
+    fn relieve(&self, factor: i64) -> Self {
+        self / Self::from_i64(factor)
+    }
+
+    fn reduce(&self, modulus: i64) -> Self {
+        self % Self::from_i64(modulus)
+    }
+}
+
+impl Worry for BigInt {
+    fn from_i64(n: i64) -> Self {
+        BigInt::from(n)
+    }
+
+    fn apply(&self, op: BinOp, rhs: &Self) -> Self {
+        check_divisor(op, *rhs == BigInt::from(0));
+        match op {
+            BinOp::Add => self + rhs,
+            BinOp::Sub => self - rhs,
+            BinOp::Mul => self * rhs,
+            BinOp::Div => self / rhs,
+            BinOp::Rem => self % rhs,
+        }
+    }
+
+    fn negate(&self) -> Self {
+        -self
+    }
+
+    fn is_divisible_by(&self, divisor: i64) -> bool {
+        self % divisor == BigInt::from(0)
+    }
+
+    fn relieve(&self, factor: i64) -> Self {
+        let quotient = self / factor;
+        if self % factor < BigInt::from(0) {
+            quotient - 1
+        } else {
+            quotient
+        }
+    }
+
+    fn reduce(&self, modulus: i64) -> Self {
+        num_integer::Integer::mod_floor(self, &BigInt::from(modulus))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Modular,
+    U128,
+    Big,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "i64" | "modular" => Ok(Backend::Modular),
+            "u128" => Ok(Backend::U128),
+            "big" | "bigint" => Ok(Backend::Big),
+            _ => Err(format!("unknown backend: {}", s)),
+        }
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn lcm(values: impl IntoIterator<Item = i64>) -> i64 {
+    values.into_iter().fold(1, |acc, v| acc / gcd(acc, v) * v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_lcm() {
+        assert_eq!(lcm([4, 6, 10]), 60);
+        assert_eq!(lcm([23, 19, 13, 17]), 23 * 19 * 13 * 17);
+    }
+
+    #[test]
+    pub fn test_relieve_floors() {
+        assert_eq!(Worry::relieve(&-7i64, 3), -3);
+        assert_eq!(BigInt::from(-7).relieve(3), BigInt::from(-3));
+        assert_eq!(Worry::relieve(&7u128, 3), 2);
+    }
+
+    #[test]
+    pub fn test_reduce() {
+        assert_eq!(Worry::reduce(&-7i64, 5), 3);
+        assert_eq!(Worry::reduce(&17u128, 5), 2);
+        assert_eq!(BigInt::from(-7).reduce(5), BigInt::from(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "worry divided by zero")]
+    pub fn test_divide_by_zero() {
+        7i64.apply(BinOp::Div, &0);
+    }
+}
@@ -0,0 +1,20 @@
+use day_04::process_sweep_pairs;
+use std::{env, fs};
+
+// the full input has over a million overlapping pairs
+const DEFAULT_LIMIT: usize = 20;
+
+// lists the first 20 overlapping pairs, pass --limit N to change that or --all to list every one
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let limit = match args.as_slice() {
+        [flag, n] if flag == "--limit" => Some(n.parse().expect("Not a number")),
+        [flag] if flag == "--all" => None,
+        [] => Some(DEFAULT_LIMIT),
+        _ => panic!("Usage: sweep [--limit N | --all]"),
+    };
+
+    let file = fs::read_to_string("./input.txt").expect("Unable to read file");
+
+    println!("Sweep: {}", process_sweep_pairs(&file, limit));
+}
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+// a set of u32 values stored as sorted, non-overlapping, non-adjacent ranges
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_range(range: RangeInclusive<u32>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+
+    pub fn ranges(&self) -> &[RangeInclusive<u32>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // number of values in the set, u64 because 0..=u32::MAX doesn't fit in a u32
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|r| (*r.end() - *r.start()) as u64 + 1)
+            .sum()
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<u32>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let mut start = *range.start();
+        let mut end = *range.end();
+
+        // everything that overlaps or touches the new range gets merged into it
+        let first = self
+            .ranges
+            .partition_point(|r| r.end().saturating_add(1) < start);
+        let mut last = first;
+        while last < self.ranges.len() && *self.ranges[last].start() <= end.saturating_add(1) {
+            start = start.min(*self.ranges[last].start());
+            end = end.max(*self.ranges[last].end());
+            last += 1;
+        }
+
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    pub fn contains(&self, value: u32) -> bool {
+        let idx = self.ranges.partition_point(|r| *r.end() < value);
+        idx < self.ranges.len() && *self.ranges[idx].start() <= value
+    }
+
+    pub fn contains_range(&self, range: &RangeInclusive<u32>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+
+        let idx = self.ranges.partition_point(|r| r.end() < range.start());
+        idx < self.ranges.len()
+            && self.ranges[idx].start() <= range.start()
+            && self.ranges[idx].end() >= range.end()
+    }
+
+    pub fn is_superset(&self, other: &IntervalSet) -> bool {
+        other.ranges.iter().all(|r| self.contains_range(r))
+    }
+
+    pub fn is_subset(&self, other: &IntervalSet) -> bool {
+        other.is_superset(self)
+    }
+
+    pub fn overlaps(&self, other: &IntervalSet) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut merged = Vec::with_capacity(self.ranges.len() + other.ranges.len());
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            if x.start() <= y.start() {
+                merged.push(a.next().unwrap().clone());
+            } else {
+                merged.push(b.next().unwrap().clone());
+            }
+        }
+        merged.extend(a.cloned());
+        merged.extend(b.cloned());
+
+        IntervalSet {
+            ranges: coalesce(merged),
+        }
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let a = &self.ranges[i];
+            let b = &other.ranges[j];
+
+            let start = *a.start().max(b.start());
+            let end = *a.end().min(b.end());
+            if start <= end {
+                ranges.push(start..=end);
+            }
+
+            // advance whichever range finishes first
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+
+        for range in &self.ranges {
+            let mut start = *range.start();
+            let end = *range.end();
+            let mut exhausted = false;
+
+            // skip everything in other that ends before this range
+            while j < other.ranges.len() && *other.ranges[j].end() < start {
+                j += 1;
+            }
+
+            let mut k = j;
+            while k < other.ranges.len() && *other.ranges[k].start() <= end {
+                let cut = &other.ranges[k];
+                if *cut.start() > start {
+                    ranges.push(start..=*cut.start() - 1);
+                }
+                if *cut.end() >= end {
+                    exhausted = true;
+                    break;
+                }
+                start = *cut.end() + 1;
+                k += 1;
+            }
+
+            if !exhausted {
+                ranges.push(start..=end);
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+
+    // the smallest single range containing the whole set
+    pub fn hull(&self) -> Option<RangeInclusive<u32>> {
+        let first = self.ranges.first()?;
+        let last = self.ranges.last()?;
+        Some(*first.start()..=*last.end())
+    }
+
+    // the parts of the hull that are not in the set
+    pub fn gaps(&self) -> IntervalSet {
+        match self.hull() {
+            Some(hull) => IntervalSet::from_range(hull).difference(self),
+            None => IntervalSet::new(),
+        }
+    }
+}
+
+// merges ranges sorted by start that overlap or touch, in one pass
+fn coalesce(sorted: Vec<RangeInclusive<u32>>) -> Vec<RangeInclusive<u32>> {
+    let mut ranges: Vec<RangeInclusive<u32>> = Vec::with_capacity(sorted.len());
+
+    for range in sorted.into_iter().filter(|r| !r.is_empty()) {
+        match ranges.last_mut() {
+            Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                *last = *last.start()..=*last.end().max(range.end());
+            }
+            _ => ranges.push(range),
+        }
+    }
+
+    ranges
+}
+
+impl FromIterator<RangeInclusive<u32>> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<u32>>>(iter: T) -> Self {
+        let mut ranges: Vec<_> = iter.into_iter().collect();
+        ranges.sort_unstable_by_key(|r| *r.start());
+
+        IntervalSet {
+            ranges: coalesce(ranges),
+        }
+    }
+}
+
+// splits the number line into pieces covered by the same number of ranges,
+// returning every non-empty piece together with how many ranges cover it
+pub fn coverage<'a, I>(ranges: I) -> Vec<(RangeInclusive<u32>, usize)>
+where
+    I: IntoIterator<Item = &'a RangeInclusive<u32>>,
+{
+    // +1 where a range starts, -1 one past where it ends
+    let mut events: BTreeMap<u64, i64> = BTreeMap::new();
+    for range in ranges {
+        if range.is_empty() {
+            continue;
+        }
+        *events.entry(*range.start() as u64).or_default() += 1;
+        *events.entry(*range.end() as u64 + 1).or_default() -= 1;
+    }
+
+    let mut result = Vec::new();
+    let mut depth = 0i64;
+    let mut events = events.into_iter().peekable();
+
+    while let Some((at, delta)) = events.next() {
+        depth += delta;
+        if let Some((next, _)) = events.peek() {
+            if depth > 0 {
+                result.push((at as u32..=(*next - 1) as u32, depth as usize));
+            }
+        }
+    }
+
+    result
+}
+
+// values covered by exactly `k` of the given ranges
+pub fn covered_exactly<'a, I>(ranges: I, k: usize) -> IntervalSet
+where
+    I: IntoIterator<Item = &'a RangeInclusive<u32>>,
+{
+    coverage(ranges)
+        .into_iter()
+        .filter(|(_, count)| *count == k)
+        .map(|(range, _)| range)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_insert_merges() {
+        let set: IntervalSet = [1..=3, 7..=9, 4..=5, 20..=20].into_iter().collect();
+        assert_eq!(set.ranges(), &[1..=5, 7..=9, 20..=20]);
+        assert_eq!(set.len(), 9);
+    }
+
+    #[test]
+    pub fn test_collect_matches_insert() {
+        // short fragmented ranges in no particular order, some empty
+        let ranges: Vec<RangeInclusive<u32>> = (0..500u32)
+            .map(|i| {
+                let start = i * 7919 % 1000;
+                start + 1..=start + i % 5
+            })
+            .collect();
+
+        let mut inserted = IntervalSet::new();
+        for range in &ranges {
+            inserted.insert(range.clone());
+        }
+        let collected: IntervalSet = ranges.iter().cloned().collect();
+        assert_eq!(collected, inserted);
+
+        let (evens, odds): (Vec<_>, Vec<_>) = ranges.into_iter().partition(|r| r.start() % 2 == 0);
+        let union = evens
+            .into_iter()
+            .collect::<IntervalSet>()
+            .union(&odds.into_iter().collect());
+        assert_eq!(union, collected);
+    }
+
+    #[test]
+    pub fn test_set_operations() {
+        let a: IntervalSet = [1..=10, 20..=30].into_iter().collect();
+        let b: IntervalSet = [5..=25].into_iter().collect();
+
+        assert_eq!(a.union(&b).ranges(), &[1..=30]);
+        assert_eq!(a.intersection(&b).ranges(), &[5..=10, 20..=25]);
+        assert_eq!(a.difference(&b).ranges(), &[1..=4, 26..=30]);
+        assert_eq!(b.difference(&a).ranges(), &[11..=19]);
+        assert!(a.contains(25) && !a.contains(15));
+        assert!(a.contains_range(&(2..=9)) && !a.contains_range(&(9..=20)));
+        assert_eq!(a.gaps().ranges(), &[11..=19]);
+    }
+
+    #[test]
+    pub fn test_coverage() {
+        let ranges = [2..=4, 3..=6, 4..=4];
+        assert_eq!(
+            coverage(&ranges),
+            vec![(2..=2, 1), (3..=3, 2), (4..=4, 3), (5..=6, 1)]
+        );
+        assert_eq!(covered_exactly(&ranges, 1).ranges(), &[2..=2, 5..=6]);
+    }
+}
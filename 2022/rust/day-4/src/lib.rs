@@ -1,65 +1,123 @@
-pub fn process_part1(input: &str) -> String {
+use std::ops::RangeInclusive;
+
+use interval::{covered_exactly, IntervalSet};
+use sweep::{count_overlaps, find_overlaps};
+
+pub mod interval;
+pub mod sweep;
+
+fn parse_range(input: &str) -> RangeInclusive<u32> {
+    let mut range = input.split('-');
+    let start = range.next().unwrap().parse::<u32>().unwrap();
+    let end = range.next().unwrap().parse::<u32>().unwrap();
+    start..=end
+}
+
+fn parse_pairs(input: &str) -> impl Iterator<Item = (IntervalSet, IntervalSet)> + '_ {
+    input.lines().map(|l| {
+        let (one, two) = l.split_once(',').unwrap();
+        (
+            IntervalSet::from_range(parse_range(one)),
+            IntervalSet::from_range(parse_range(two)),
+        )
+    })
+}
+
+// every assignment with its 1-based line and position on that line, lines
+// can hold any number of comma separated ranges
+fn parse_labelled(input: &str) -> Vec<((usize, usize), RangeInclusive<u32>)> {
     input
         .lines()
-        .map(|l| {
-            let pairs = l.split(',');
-            let mut ranges = pairs.map(|p| {
-                let mut range = p.split('-');
-                let start = range.next().unwrap().parse::<u32>().unwrap();
-                let end = range.next().unwrap().parse::<u32>().unwrap();
-                start..=end
-            });
-
-            let one = ranges.next().unwrap();
-            let two = ranges.next().unwrap();
-
-            // check if two completely contains one
-            if one.start() >= two.start() && one.end() <= two.end() {
-                return 1;
-            }
-
-            // check if one completely contains two
-            if two.start() >= one.start() && two.end() <= one.end() {
-                return 1;
-            }
-
-            0
+        .enumerate()
+        .flat_map(|(line, l)| {
+            l.split(',')
+                .enumerate()
+                .map(move |(position, r)| ((line + 1, position + 1), parse_range(r)))
         })
-        .sum::<i32>()
+        .collect()
+}
+
+fn parse_assignments(input: &str) -> Vec<RangeInclusive<u32>> {
+    parse_labelled(input).into_iter().map(|(_, r)| r).collect()
+}
+
+pub fn process_part1(input: &str) -> String {
+    parse_pairs(input)
+        .filter(|(one, two)| one.is_subset(two) || two.is_subset(one))
+        .count()
         .to_string()
 }
 
 pub fn process_part2(input: &str) -> String {
-    input
-        .lines()
-        .map(|l| {
-            let pairs = l.split(',');
-            let mut ranges = pairs.map(|p| {
-                let mut range = p.split('-');
-                let start = range.next().unwrap().parse::<u32>().unwrap();
-                let end = range.next().unwrap().parse::<u32>().unwrap();
-                start..=end
-            });
-
-            let one = ranges.next().unwrap();
-            let two = ranges.next().unwrap();
-
-            // check if one overlaps two
-            if one.start() <= two.start() && one.end() >= two.start() {
-                return 1;
-            }
-
-            // check if two overlaps one
-            if two.start() <= one.start() && two.end() >= one.start() {
-                return 1;
-            }
-
-            0
-        })
-        .sum::<i32>()
+    parse_pairs(input)
+        .filter(|(one, two)| one.overlaps(two))
+        .count()
         .to_string()
 }
 
+// sections cleaned by exactly `k` elves across every assignment in the input
+pub fn sections_covered_by(input: &str, k: usize) -> IntervalSet {
+    covered_exactly(&parse_assignments(input), k)
+}
+
+// sections between the lowest and highest assigned section that nobody cleans
+pub fn sections_nobody_cleans(input: &str) -> IntervalSet {
+    parse_assignments(input)
+        .into_iter()
+        .collect::<IntervalSet>()
+        .gaps()
+}
+
+// compares every assignment in the input against every other one, not just
+// the one on the same line, reporting the overlap count and the most elves
+// working on the same section at once
+pub fn process_sweep(input: &str) -> String {
+    let (count, max_overlap) = count_overlaps(&parse_assignments(input));
+
+    format!(
+        "{} overlapping pairs, at most {} at once",
+        count, max_overlap
+    )
+}
+
+// the sweep summary followed by one line per overlapping pair, naming the
+// elves by line and position, cut off after `limit` pairs if given
+pub fn process_sweep_pairs(input: &str, limit: Option<usize>) -> String {
+    let labelled = parse_labelled(input);
+    let ranges = labelled.iter().map(|(_, r)| r.clone()).collect::<Vec<_>>();
+    let overlaps = find_overlaps(&ranges);
+    let limit = limit.unwrap_or(overlaps.pairs.len());
+
+    let elf = |i: usize| {
+        let ((line, position), range) = &labelled[i];
+        format!(
+            "line {} elf {} ({}-{})",
+            line,
+            position,
+            range.start(),
+            range.end()
+        )
+    };
+
+    let mut lines = vec![format!(
+        "{} overlapping pairs, at most {} at once",
+        overlaps.pairs.len(),
+        overlaps.max_overlap
+    )];
+    lines.extend(
+        overlaps
+            .pairs
+            .iter()
+            .take(limit)
+            .map(|&(a, b)| format!("{} and {}", elf(a), elf(b))),
+    );
+    if overlaps.pairs.len() > limit {
+        lines.push(format!("... {} more", overlaps.pairs.len() - limit));
+    }
+
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -79,4 +137,52 @@ mod tests {
     pub fn test_part_2() {
         assert_eq!(super::process_part2(INPUT), "4");
     }
+
+    #[test]
+    pub fn test_sections_covered_by() {
+        assert_eq!(super::sections_covered_by(INPUT, 1).ranges(), &[9..=9]);
+        assert_eq!(super::sections_covered_by(INPUT, 7).ranges(), &[4..=5]);
+    }
+
+    #[test]
+    pub fn test_sweep() {
+        assert_eq!(
+            super::process_sweep(INPUT),
+            "49 overlapping pairs, at most 8 at once"
+        );
+    }
+
+    #[test]
+    pub fn test_sweep_pairs() {
+        let pairs = super::process_sweep_pairs(INPUT, Some(2));
+        let lines = pairs.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "49 overlapping pairs, at most 8 at once");
+        assert_eq!(lines[3], "... 47 more");
+        assert_eq!(super::process_sweep_pairs(INPUT, None).lines().count(), 50);
+    }
+
+    #[test]
+    pub fn test_sweep_pair_labels() {
+        // labels come from the input, not from assuming two elves per line.
+        // pairs come out in sweep order, so sort them before comparing
+        let pairs = super::process_sweep_pairs("1-5\n3-4,9-9,4-4", None);
+        let mut lines = pairs.lines().collect::<Vec<_>>();
+        lines[1..].sort_unstable();
+        assert_eq!(
+            lines,
+            [
+                "3 overlapping pairs, at most 3 at once",
+                "line 1 elf 1 (1-5) and line 2 elf 1 (3-4)",
+                "line 1 elf 1 (1-5) and line 2 elf 3 (4-4)",
+                "line 2 elf 1 (3-4) and line 2 elf 3 (4-4)",
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_sections_nobody_cleans() {
+        let sections = super::sections_nobody_cleans("1-2,8-9\n3-4,8-8");
+        assert_eq!(sections.ranges(), &[5..=7]);
+    }
 }
@@ -0,0 +1,93 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Overlaps {
+    // index pairs into the input, lower index first, in the order the sweep finds them
+    pub pairs: Vec<(usize, usize)>,
+    // most ranges sharing a single section at the same time
+    pub max_overlap: usize,
+}
+
+// sweeps over the sorted start points while keeping the ranges that are still
+// open in a min-heap on their end. every range overlaps exactly the ones open
+// when it starts, which are handed to `on_open` along with the new range's index.
+// returns the most ranges open at once
+fn sweep(
+    ranges: &[RangeInclusive<u32>],
+    mut on_open: impl FnMut(usize, &[Reverse<(u32, usize)>]),
+) -> usize {
+    let mut order = (0..ranges.len())
+        .filter(|&i| !ranges[i].is_empty())
+        .collect::<Vec<_>>();
+    order.sort_by_key(|&i| *ranges[i].start());
+
+    let mut active: BinaryHeap<Reverse<(u32, usize)>> = BinaryHeap::new();
+    let mut max_overlap = 0;
+
+    for i in order {
+        let start = *ranges[i].start();
+
+        // anything that ended before this range starts can't overlap anything after it either
+        while let Some(Reverse((end, _))) = active.peek() {
+            if *end >= start {
+                break;
+            }
+            active.pop();
+        }
+
+        on_open(i, active.as_slice());
+
+        active.push(Reverse((*ranges[i].end(), i)));
+        max_overlap = max_overlap.max(active.len());
+    }
+
+    max_overlap
+}
+
+// every pair of overlapping ranges, O(n log n + k) for k reported pairs
+pub fn find_overlaps(ranges: &[RangeInclusive<u32>]) -> Overlaps {
+    let mut pairs = Vec::new();
+    let max_overlap = sweep(ranges, |i, open| {
+        pairs.extend(open.iter().map(|Reverse((_, j))| (i.min(*j), i.max(*j))));
+    });
+
+    Overlaps { pairs, max_overlap }
+}
+
+// the number of overlapping pairs and the most at once without listing them, O(n log n)
+pub fn count_overlaps(ranges: &[RangeInclusive<u32>]) -> (usize, usize) {
+    let mut count = 0;
+    let max_overlap = sweep(ranges, |_, open| count += open.len());
+
+    (count, max_overlap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(ranges: &[RangeInclusive<u32>]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for i in 0..ranges.len() {
+            for j in i + 1..ranges.len() {
+                if ranges[i].start() <= ranges[j].end() && ranges[j].start() <= ranges[i].end() {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    pub fn test_find_overlaps() {
+        let ranges = [1..=3, 3..=5, 6..=6, 2..=2, 10..=12, 0..=20];
+        let mut overlaps = find_overlaps(&ranges);
+        overlaps.pairs.sort_unstable();
+
+        assert_eq!(overlaps.pairs, brute_force(&ranges));
+        assert_eq!(overlaps.max_overlap, 3);
+        assert_eq!(count_overlaps(&ranges), (overlaps.pairs.len(), 3));
+    }
+}
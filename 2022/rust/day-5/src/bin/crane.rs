@@ -0,0 +1,17 @@
+use day_05::{crane, try_process_with_crane};
+use std::{env, fs, process};
+
+fn main() {
+    let model = env::args().nth(1).unwrap_or_else(|| "9000".to_string());
+    let crane = crane::from_name(&model).unwrap_or_else(|e| panic!("{}", e));
+
+    let file = fs::read_to_string("./input.txt").expect("Unable to read file");
+
+    match try_process_with_crane(&file, crane.as_ref()) {
+        Ok(top_row) => println!("{}: {}", crane.name(), top_row),
+        Err(e) => {
+            eprintln!("{}: {}", crane.name(), e);
+            process::exit(1);
+        }
+    }
+}
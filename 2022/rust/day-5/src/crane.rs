@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use crate::Crate;
+
+pub trait Crane {
+    fn name(&self) -> String;
+
+    // move `amount` crates from the top of `from` to the top of `to`,
+    // the interpreter has already turned the 1-based stack numbers into indexes
+    fn move_crates(&self, stacks: &mut [VecDeque<Crate>], amount: usize, from: usize, to: usize);
+}
+
+// takes the top `amount` crates off a stack, bottom-most first
+fn lift(stack: &mut VecDeque<Crate>, amount: usize) -> VecDeque<Crate> {
+    stack.split_off(stack.len() - amount)
+}
+
+// moves one crate at a time, so the moved crates end up reversed
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn move_crates(&self, stacks: &mut [VecDeque<Crate>], amount: usize, from: usize, to: usize) {
+        let crates = lift(&mut stacks[from], amount);
+        stacks[to].extend(crates.into_iter().rev());
+    }
+}
+
+// moves all crates at once, keeping their order
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn move_crates(&self, stacks: &mut [VecDeque<Crate>], amount: usize, from: usize, to: usize) {
+        let mut crates = lift(&mut stacks[from], amount);
+        stacks[to].append(&mut crates);
+    }
+}
+
+// moves at most `capacity` crates at a time, each batch keeps its order
+pub struct BatchCrane {
+    pub capacity: usize,
+}
+
+impl Crane for BatchCrane {
+    fn name(&self) -> String {
+        format!("Batch crane ({})", self.capacity)
+    }
+
+    fn move_crates(&self, stacks: &mut [VecDeque<Crate>], amount: usize, from: usize, to: usize) {
+        let mut remaining = amount;
+        while remaining > 0 {
+            let batch = remaining.min(self.capacity.max(1));
+            let mut crates = lift(&mut stacks[from], batch);
+            stacks[to].append(&mut crates);
+            remaining -= batch;
+        }
+    }
+}
+
+// grabs two crates at a time and puts them down the other way around
+pub struct PairSwapCrane;
+
+impl Crane for PairSwapCrane {
+    fn name(&self) -> String {
+        "Pair swapping crane".to_string()
+    }
+
+    fn move_crates(&self, stacks: &mut [VecDeque<Crate>], amount: usize, from: usize, to: usize) {
+        let mut remaining = amount;
+        while remaining > 0 {
+            let batch = remaining.min(2);
+            let crates = lift(&mut stacks[from], batch);
+            stacks[to].extend(crates.into_iter().rev());
+            remaining -= batch;
+        }
+    }
+}
+
+// 9000, 9001, batch:<capacity> or pairs
+pub fn from_name(name: &str) -> Result<Box<dyn Crane>, String> {
+    let unknown = || {
+        format!(
+            "Unknown crane model: {} (try 9000, 9001, batch:<n> or pairs)",
+            name
+        )
+    };
+
+    match name {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        "pairs" => Ok(Box::new(PairSwapCrane)),
+        _ => {
+            let capacity = name
+                .strip_prefix("batch:")
+                .and_then(|c| c.parse().ok())
+                .ok_or_else(unknown)?;
+            if capacity == 0 {
+                return Err(format!("{} can't lift any crates", name));
+            }
+            Ok(Box::new(BatchCrane { capacity }))
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{Crate, Error, Warehouse};
+
+// every column in the drawing is 3 characters wide with a single space between them
+const CELL_WIDTH: usize = 3;
+
+fn cells(line: &str) -> Vec<String> {
+    let chars = line.chars().collect::<Vec<_>>();
+    chars
+        .chunks(CELL_WIDTH + 1)
+        .map(|chunk| chunk.iter().take(CELL_WIDTH).collect())
+        .collect()
+}
+
+fn parse_cell(cell: &str, row: usize, column: usize) -> Result<Option<Crate>, Error> {
+    let mut chars = cell.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some('['), Some(c), Some(']')) if c != ' ' => Ok(Some(Crate(c))),
+        _ if cell.trim().is_empty() => Ok(None),
+        _ => Err(format!(
+            "line {}: unexpected '{}' in column {}",
+            row + 1,
+            cell,
+            column + 1
+        )
+        .into()),
+    }
+}
+
+fn parse_footer(line: &str, row: usize) -> Result<usize, Error> {
+    let labels = cells(line);
+
+    for (index, label) in labels.iter().enumerate() {
+        let number = label.trim().parse::<usize>().map_err(|_| {
+            format!(
+                "line {}: expected stack label {}, found '{}'",
+                row + 1,
+                index + 1,
+                label.trim()
+            )
+        })?;
+
+        if number != index + 1 {
+            return Err(format!(
+                "line {}: stack labels out of order, expected {} but found {}",
+                row + 1,
+                index + 1,
+                number
+            )
+            .into());
+        }
+    }
+
+    Ok(labels.len())
+}
+
+impl FromStr for Warehouse {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let drawing = input
+            .lines()
+            .take_while(|l| !l.trim().is_empty())
+            .collect::<Vec<_>>();
+
+        let (footer, rows) = drawing.split_last().ok_or("missing crate drawing")?;
+        let count = parse_footer(footer, rows.len())?;
+
+        let mut stacks: Vec<VecDeque<Crate>> = (0..count).map(|_| VecDeque::new()).collect();
+
+        // walk the drawing bottom to top so crates are pushed in stacking order
+        for (row, line) in rows.iter().enumerate().rev() {
+            let cells = cells(line);
+            if cells.len() > count {
+                return Err(format!(
+                    "line {}: {} columns but only {} stack labels",
+                    row + 1,
+                    cells.len(),
+                    count
+                )
+                .into());
+            }
+
+            for (column, cell) in cells.iter().enumerate() {
+                let Some(crate_) = parse_cell(cell, row, column)? else {
+                    continue;
+                };
+
+                // every row below this one must have a crate in this column
+                if stacks[column].len() != rows.len() - row - 1 {
+                    return Err(format!(
+                        "line {}: crate in column {} is floating",
+                        row + 1,
+                        column + 1
+                    )
+                    .into());
+                }
+
+                stacks[column].push_back(crate_);
+            }
+        }
+
+        Ok(Warehouse { stacks })
+    }
+}
+
+// renders the warehouse exactly like the puzzle input draws it
+impl Display for Warehouse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let height = self.stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+
+        for level in (0..height).rev() {
+            let row = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(crate_) => format!("[{}]", crate_.0),
+                    None => " ".repeat(CELL_WIDTH),
+                })
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+
+        let footer = (1..=self.stacks.len())
+            .map(|label| format!("{:^width$}", label, width = CELL_WIDTH))
+            .collect::<Vec<_>>();
+        write!(f, "{}", footer.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Warehouse;
+
+    const DRAWING: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 ";
+
+    #[test]
+    pub fn test_round_trip() {
+        let warehouse = DRAWING.parse::<Warehouse>().unwrap();
+        assert_eq!(warehouse.top_row(), "NDP");
+        assert_eq!(warehouse.to_string(), DRAWING);
+        assert_eq!(
+            warehouse.to_string().parse::<Warehouse>().unwrap(),
+            warehouse
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_drawings() {
+        assert!("[A]\n 2 ".parse::<Warehouse>().is_err());
+        assert!("[A] [B]\n 1 ".parse::<Warehouse>().is_err());
+        assert!("[A]    \n    [B]\n 1   2 ".parse::<Warehouse>().is_err());
+        assert!("(A)\n 1 ".parse::<Warehouse>().is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+};
+
+use crate::{crane::Crane, Crate, Error, Warehouse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub amount: usize,
+    // stack numbers as written in the input, starting at 1
+    pub from: usize,
+    pub to: usize,
+    // line in the input file this instruction came from, starting at 1
+    pub line: usize,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
+    }
+}
+
+impl Move {
+    pub fn parse(input: &str, line: usize) -> Result<Self, Error> {
+        let words = input.split_whitespace().collect::<Vec<_>>();
+
+        let ["move", amount, "from", from, "to", to] = words[..] else {
+            return Err(format!(
+                "line {}: expected 'move N from A to B', found '{}'",
+                line, input
+            )
+            .into());
+        };
+
+        let number = |word: &str| {
+            word.parse::<usize>()
+                .map_err(|_| format!("line {}: '{}' is not a number", line, word))
+        };
+
+        Ok(Move {
+            amount: number(amount)?,
+            from: number(from)?,
+            to: number(to)?,
+            line,
+        })
+    }
+}
+
+// parses every instruction below the crate drawing
+pub fn parse_moves(input: &str) -> Result<Vec<Move>, Error> {
+    input
+        .lines()
+        .enumerate()
+        .skip_while(|(_, l)| !l.trim().is_empty())
+        .skip(1)
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(index, l)| Move::parse(l, index + 1))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionError {
+    pub instruction: Move,
+    pub reason: String,
+}
+
+impl Display for ExecutionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {}",
+            self.instruction.line, self.instruction, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ExecutionError {}
+
+impl Warehouse {
+    pub fn validate(&self, instruction: &Move) -> Result<(), ExecutionError> {
+        let error = |reason: String| ExecutionError {
+            instruction: *instruction,
+            reason,
+        };
+
+        for stack in [instruction.from, instruction.to] {
+            if stack == 0 || stack > self.stacks.len() {
+                return Err(error(format!(
+                    "stack {} does not exist, there are {} stacks",
+                    stack,
+                    self.stacks.len()
+                )));
+            }
+        }
+
+        let available = self.stacks[instruction.from - 1].len();
+        if available < instruction.amount {
+            return Err(error(format!(
+                "stack {} only has {} crates",
+                instruction.from, available
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn apply(&mut self, instruction: &Move, crane: &dyn Crane) -> Result<(), ExecutionError> {
+        self.validate(instruction)?;
+
+        crane.move_crates(
+            &mut self.stacks,
+            instruction.amount,
+            instruction.from - 1,
+            instruction.to - 1,
+        );
+
+        Ok(())
+    }
+}
+
+// steps a warehouse through a list of moves, remembering enough to walk back again
+pub struct Execution<'a> {
+    pub warehouse: Warehouse,
+    moves: Vec<Move>,
+    crane: &'a dyn Crane,
+    // the crates each executed move lifted, in their original order
+    history: Vec<VecDeque<Crate>>,
+    // how far we've been before undoing, redo can go back up to here
+    furthest: usize,
+}
+
+impl<'a> Execution<'a> {
+    pub fn new(warehouse: Warehouse, moves: Vec<Move>, crane: &'a dyn Crane) -> Self {
+        Execution {
+            warehouse,
+            moves,
+            crane,
+            history: Vec::new(),
+            furthest: 0,
+        }
+    }
+
+    // number of instructions executed so far
+    pub fn position(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.position() == self.moves.len()
+    }
+
+    // executes the next instruction, returns false when there is nothing left to do
+    pub fn step(&mut self) -> Result<bool, ExecutionError> {
+        let Some(instruction) = self.moves.get(self.position()).copied() else {
+            return Ok(false);
+        };
+
+        self.warehouse.validate(&instruction)?;
+
+        let from = &self.warehouse.stacks[instruction.from - 1];
+        let lifted = from
+            .iter()
+            .skip(from.len() - instruction.amount)
+            .cloned()
+            .collect();
+
+        self.warehouse.apply(&instruction, self.crane)?;
+        self.history.push(lifted);
+        self.furthest = self.furthest.max(self.position());
+
+        Ok(true)
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(mut lifted) = self.history.pop() else {
+            return false;
+        };
+
+        // whatever the crane did, the moved crates are now on top of the target stack
+        let instruction = self.moves[self.position()];
+        let to = &mut self.warehouse.stacks[instruction.to - 1];
+        to.truncate(to.len() - instruction.amount);
+        self.warehouse.stacks[instruction.from - 1].append(&mut lifted);
+
+        true
+    }
+
+    pub fn redo(&mut self) -> Result<bool, ExecutionError> {
+        if self.position() >= self.furthest {
+            return Ok(false);
+        }
+
+        self.step()
+    }
+
+    // moves forwards or backwards until `index` instructions have been executed
+    pub fn seek(&mut self, index: usize) -> Result<(), ExecutionError> {
+        let index = index.min(self.moves.len());
+
+        while self.position() > index {
+            self.undo();
+        }
+
+        while self.position() < index {
+            self.step()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> Result<(), ExecutionError> {
+        self.seek(self.moves.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::CrateMover9001;
+    use crate::tests::INPUT;
+
+    #[test]
+    pub fn test_undo_redo() {
+        let warehouse = Warehouse::from_input(INPUT);
+        let moves = parse_moves(INPUT).unwrap();
+        let mut execution = Execution::new(warehouse.clone(), moves, &CrateMover9001);
+
+        execution.seek(2).unwrap();
+        assert_eq!(execution.warehouse.top_row(), "CD");
+        let after_two = execution.warehouse.clone();
+
+        execution.run().unwrap();
+        assert!(execution.is_done());
+        assert_eq!(execution.warehouse.top_row(), "MCD");
+
+        assert!(execution.undo());
+        assert!(execution.undo());
+        assert_eq!(execution.warehouse, after_two);
+
+        assert!(execution.redo().unwrap());
+        assert!(execution.redo().unwrap());
+        assert!(!execution.redo().unwrap());
+        assert_eq!(execution.warehouse.top_row(), "MCD");
+
+        execution.seek(0).unwrap();
+        assert_eq!(execution.warehouse, warehouse);
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let input = INPUT.replace("move 3 from 1 to 3", "move 4 from 1 to 3");
+        let moves = parse_moves(&input).unwrap();
+        let mut execution = Execution::new(Warehouse::from_input(&input), moves, &CrateMover9001);
+
+        let error = execution.run().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 7: move 4 from 1 to 3: stack 1 only has 3 crates"
+        );
+        assert_eq!(execution.position(), 1);
+
+        let input = INPUT.replace("move 1 from 1 to 2", "move 1 from 1 to 4");
+        let moves = parse_moves(&input).unwrap();
+        let mut execution = Execution::new(Warehouse::from_input(&input), moves, &CrateMover9001);
+        assert_eq!(
+            execution.run().unwrap_err().to_string(),
+            "line 9: move 1 from 1 to 4: stack 4 does not exist, there are 3 stacks"
+        );
+
+        let input = INPUT.replace("move 2 from 2", "move two from 2");
+        assert_eq!(
+            parse_moves(&input).unwrap_err().to_string(),
+            "line 8: 'two' is not a number"
+        );
+    }
+}
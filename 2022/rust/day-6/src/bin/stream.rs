@@ -0,0 +1,17 @@
+use day_06::decoder::{decode, Event};
+use std::io;
+
+// reads a datastream from stdin, e.g. `cat input.txt | cargo run --bin stream`
+fn main() {
+    decode(io::stdin().lock(), |event| match event {
+        Event::StartOfPacket(offset) => println!("start-of-packet at {}", offset),
+        Event::StartOfMessage(offset) => println!("start-of-message at {}", offset),
+        Event::Message { offset, data } => println!(
+            "message at {} ({} bytes): {}",
+            offset,
+            data.len(),
+            String::from_utf8_lossy(&data).trim_end()
+        ),
+    })
+    .expect("Unable to read stream");
+}
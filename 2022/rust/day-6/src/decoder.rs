@@ -0,0 +1,197 @@
+use std::io::{self, Read};
+
+use crate::marker::ByteScanner;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    // offsets are the number of bytes read when the marker completed
+    StartOfPacket(usize),
+    StartOfMessage(usize),
+    // everything between one start-of-message marker and the next
+    Message { offset: usize, data: Vec<u8> },
+}
+
+// a datastream decoder that can be fed the signal a chunk at a time
+//
+// after the first start-of-message marker the stream is split into messages:
+// each message runs until the next start-of-message marker, which has to be
+// made up of bytes that come after the previous marker
+pub struct Decoder {
+    message_window: usize,
+    packet: Option<ByteScanner>,
+    message: ByteScanner,
+    offset: usize,
+    // start of the current message, None until the first message marker
+    message_start: Option<usize>,
+    buffer: Vec<u8>,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Decoder::new(4, 14)
+    }
+}
+
+impl Decoder {
+    pub fn new(packet_window: usize, message_window: usize) -> Self {
+        Decoder {
+            message_window,
+            packet: Some(ByteScanner::new(packet_window)),
+            message: ByteScanner::new(message_window),
+            offset: 0,
+            message_start: None,
+            buffer: Vec::new(),
+        }
+    }
+
+    // number of bytes fed so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        for &byte in chunk {
+            let index = self.offset;
+            self.offset += 1;
+
+            if let Some(packet) = &mut self.packet {
+                if let Some(at) = packet.feed(index, byte) {
+                    events.push(Event::StartOfPacket(at));
+                    self.packet = None;
+                }
+            }
+
+            if self.message_start.is_some() {
+                self.buffer.push(byte);
+            }
+
+            if let Some(at) = self.message.feed(index, byte) {
+                if let Some(start) = self.message_start {
+                    // the marker itself isn't part of the message it ends
+                    let data = self.buffer[..self.buffer.len() - self.message_window].to_vec();
+                    events.push(Event::Message {
+                        offset: start,
+                        data,
+                    });
+                }
+
+                events.push(Event::StartOfMessage(at));
+                self.message_start = Some(at);
+                self.message.restart(at);
+                self.buffer.clear();
+            }
+        }
+
+        events
+    }
+
+    // flushes the message that was still being read when the stream ended
+    pub fn finish(self) -> Vec<Event> {
+        match self.message_start {
+            Some(offset) => vec![Event::Message {
+                offset,
+                data: self.buffer,
+            }],
+            None => Vec::new(),
+        }
+    }
+}
+
+// reads the whole stream through a decoder, calling `on_event` as soon as
+// something is detected rather than when the reader is exhausted
+pub fn decode<R: Read>(mut reader: R, mut on_event: impl FnMut(Event)) -> io::Result<()> {
+    let mut decoder = Decoder::default();
+    let mut chunk = [0; 4096];
+
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        decoder
+            .feed(&chunk[..read])
+            .into_iter()
+            .for_each(&mut on_event);
+    }
+
+    decoder.finish().into_iter().for_each(on_event);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_markers_across_chunks() {
+        let input = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+
+        // same events no matter how the stream is chunked
+        for size in [1, 3, 7, input.len()] {
+            let mut decoder = Decoder::default();
+            let events = input
+                .chunks(size)
+                .flat_map(|chunk| decoder.feed(chunk))
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                events,
+                vec![Event::StartOfPacket(7), Event::StartOfMessage(19)]
+            );
+            assert_eq!(
+                decoder.finish(),
+                vec![Event::Message {
+                    offset: 19,
+                    data: b"jfqwrcgsmlb".to_vec()
+                }]
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_framing() {
+        let mut decoder = Decoder::new(2, 3);
+        let mut events = decoder.feed(b"aabcdaaxyzhello");
+        events.extend(decoder.finish());
+
+        let message = |offset, data: &[u8]| Event::Message {
+            offset,
+            data: data.to_vec(),
+        };
+
+        assert_eq!(
+            events,
+            vec![
+                Event::StartOfPacket(3),
+                Event::StartOfMessage(4),
+                message(4, b"da"),
+                Event::StartOfMessage(9),
+                message(9, b""),
+                Event::StartOfMessage(12),
+                message(12, b"llo"),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_decode_reader() {
+        let input = b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
+
+        let mut events = Vec::new();
+        decode(&input[..], |e| events.push(e)).unwrap();
+
+        let mut decoder = Decoder::default();
+        let mut expected = decoder.feed(input);
+        expected.extend(decoder.finish());
+
+        assert_eq!(events, expected);
+        assert_eq!(events[0], Event::StartOfPacket(10));
+        assert_eq!(events[1], Event::StartOfMessage(29));
+    }
+}
@@ -0,0 +1,108 @@
+use std::{collections::HashMap, hash::Hash};
+
+// tracks where the current run of distinct symbols starts using the index each
+// symbol was last seen at, so every symbol is looked at exactly once
+pub(crate) struct Scanner {
+    window: usize,
+    start: usize,
+}
+
+impl Scanner {
+    pub(crate) fn new(window: usize) -> Self {
+        assert!(window > 0, "marker window must be at least 1");
+        Scanner { window, start: 0 }
+    }
+
+    // forget everything before `index`, the next marker has to fit entirely after it
+    pub(crate) fn restart(&mut self, index: usize) {
+        self.start = index;
+    }
+
+    // feeds the symbol at `index` that was previously seen at `last`,
+    // returns the marker position if the window ending here is all distinct
+    pub(crate) fn feed(&mut self, index: usize, last: Option<usize>) -> Option<usize> {
+        if let Some(last) = last {
+            if last >= self.start {
+                self.start = last + 1;
+            }
+        }
+
+        (index + 1 - self.start >= self.window).then_some(index + 1)
+    }
+}
+
+// positions (number of symbols read) after every window of `window` distinct symbols
+pub fn find_marker<T, I>(stream: I, window: usize) -> Vec<usize>
+where
+    T: Eq + Hash,
+    I: IntoIterator<Item = T>,
+{
+    let mut scanner = Scanner::new(window);
+    let mut last_seen = HashMap::new();
+
+    stream
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, symbol)| scanner.feed(index, last_seen.insert(symbol, index)))
+        .collect()
+}
+
+// a scanner over bytes, using a flat table instead of hashing every byte
+pub(crate) struct ByteScanner {
+    scanner: Scanner,
+    last_seen: [Option<usize>; 256],
+}
+
+impl ByteScanner {
+    pub(crate) fn new(window: usize) -> Self {
+        ByteScanner {
+            scanner: Scanner::new(window),
+            last_seen: [None; 256],
+        }
+    }
+
+    pub(crate) fn restart(&mut self, index: usize) {
+        self.scanner.restart(index);
+    }
+
+    pub(crate) fn feed(&mut self, index: usize, byte: u8) -> Option<usize> {
+        let last = self.last_seen[byte as usize].replace(index);
+        self.scanner.feed(index, last)
+    }
+}
+
+// same as find_marker, specialised for bytes
+pub fn find_marker_bytes(stream: &[u8], window: usize) -> Vec<usize> {
+    let mut scanner = ByteScanner::new(window);
+
+    stream
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &byte)| scanner.feed(index, byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_all_markers() {
+        assert_eq!(find_marker("abcabd".chars(), 3), vec![3, 4, 5, 6]);
+        assert_eq!(find_marker("aabbaab".chars(), 2), vec![3, 5, 7]);
+        assert_eq!(find_marker_bytes(b"aabbaab", 2), vec![3, 5, 7]);
+        assert_eq!(find_marker([1, 1, 1], 1), vec![1, 2, 3]);
+        assert!(find_marker_bytes(b"abc", 4).is_empty());
+    }
+
+    #[test]
+    pub fn test_chars_and_bytes_agree() {
+        let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        for window in 1..=14 {
+            assert_eq!(
+                find_marker(input.chars(), window),
+                find_marker_bytes(input.as_bytes(), window)
+            );
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use day_07::{process_cleanup, DISK_SIZE, UPDATE_SIZE};
+use std::{env, fs};
+
+// cargo run --bin cleanup -- [disk size] [required free space]
+fn main() {
+    let mut args = env::args()
+        .skip(1)
+        .map(|a| a.parse::<usize>().expect("Not a number"));
+    let disk_size = args.next().unwrap_or(DISK_SIZE);
+    let free = args.next().unwrap_or(UPDATE_SIZE);
+
+    let file = fs::read_to_string("./input.txt").expect("Unable to read file");
+
+    println!("Cleanup: {}", process_cleanup(&file, disk_size, free));
+}
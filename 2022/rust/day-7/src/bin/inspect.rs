@@ -0,0 +1,27 @@
+use day_07::{
+    render::{render_du, render_tree, to_json},
+    transcript::interpret,
+};
+use std::{env, fs};
+
+// cargo run --bin inspect -- [tree|du|json] [input file]
+fn main() {
+    let mut args = env::args().skip(1);
+    let view = args.next().unwrap_or_else(|| "tree".to_string());
+    let path = args.next().unwrap_or_else(|| "./input.txt".to_string());
+
+    let file = fs::read_to_string(path).expect("Unable to read file");
+    let transcript = interpret(&file);
+
+    for issue in &transcript.issues {
+        eprintln!("{}", issue);
+    }
+
+    let fs = &transcript.fs;
+    match view.as_str() {
+        "tree" => print!("{}", render_tree(fs, fs.root())),
+        "du" => print!("{}", render_du(fs)),
+        "json" => print!("{}", to_json(fs, fs.root())),
+        _ => panic!("Unknown view: {} (try tree, du or json)", view),
+    }
+}
@@ -1,169 +1,74 @@
-use std::path::PathBuf;
+use planner::{plan_multiple, plan_single};
+use transcript::interpret;
+use vfs::{FileSystem, NodeId, NodeKind};
 
-pub fn parse_input(input: &str) {
-    let mut cwd: Vec<&str> = vec![];
+pub mod planner;
+pub mod render;
+pub mod transcript;
+pub mod vfs;
 
-    // delete directory result if it exists
-    if std::path::Path::new("result").exists() {
-        std::fs::remove_dir_all("result").unwrap();
-    }
+pub const DISK_SIZE: usize = 70000000;
+pub const UPDATE_SIZE: usize = 30000000;
 
-    // create a directory called result
-    std::fs::create_dir("result").unwrap();
-
-    let _ = input
-        .lines()
-        .map(|l| {
-            if l.starts_with("$") {
-                // split on whitespace
-                let mut parts = l.split_whitespace();
-                let _ = parts.next(); // skip the $
-
-                let command = parts.next().unwrap();
-
-                match command {
-                    "cd" => {
-                        let path = parts.next().unwrap();
-                        if path.starts_with("/") {
-                            cwd = vec!["/"];
-                        }
-
-                        let path_parts = path.split("/");
-
-                        for p in path_parts {
-                            match p {
-                                ".." => {
-                                    cwd.pop();
-                                }
-                                "." => {}
-                                "" => {}
-                                _ => {
-                                    cwd.push(p);
-                                }
-                            }
-                        }
-                    }
-                    _ => { /* do nothing */ }
-                }
-            }
-
-            if l.chars().nth(0).unwrap().is_numeric() {
-                let mut parts = l.split_whitespace();
-                let size = parts.next().unwrap().parse::<usize>().unwrap();
-                let name = parts.next().unwrap().to_string();
-
-                let file =
-                    std::fs::File::create(format!("result/{}/{}", cwd.join("/"), name)).unwrap();
-                file.set_len(size as u64).unwrap();
-            } else if l.starts_with("dir") {
-                let mut parts = l.split_whitespace();
-                let _ = parts.next(); // skip the dir
-                let name = parts.next().unwrap().to_string();
-
-                std::fs::create_dir(format!("result/{}/{}", cwd.join("/"), name)).unwrap();
-            }
-        })
-        .collect::<()>();
+pub fn parse_input(input: &str) -> FileSystem {
+    interpret(input).fs
 }
 
-pub fn get_dir_size(path: &PathBuf) -> usize {
-    let mut total_size = 0;
-    for entry in std::fs::read_dir(path).unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
-        if path.is_file() {
-            total_size += path.metadata().unwrap().len() as usize;
-        } else if path.is_dir() {
-            total_size += get_dir_size(&path);
-        }
-    }
-
-    total_size
+pub fn get_dir_size(fs: &FileSystem, dir: NodeId) -> usize {
+    fs.children(dir)
+        .iter()
+        .map(|&child| match fs.node(child).kind {
+            NodeKind::File { size } => size,
+            NodeKind::Dir { .. } => get_dir_size(fs, child),
+        })
+        .sum()
 }
 
-pub fn get_dir_size_max(path: &PathBuf, max: usize) -> usize {
-    let mut total_size = 0;
-
-    // if the current directory is smaller than max, add it to the total
-    let size = get_dir_size(path);
-    if size <= max {
-        total_size += size;
-    }
-
-    // check subdirectories
-    for entry in std::fs::read_dir(path).unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
-        if path.is_dir() {
-            total_size += get_dir_size_max(&path, max);
-        }
-    }
-
-    total_size
+pub fn get_dir_size_max(fs: &FileSystem, dir: NodeId, max: usize) -> usize {
+    fs.directories_under(dir)
+        .into_iter()
+        .map(|d| get_dir_size(fs, d))
+        .filter(|&size| size <= max)
+        .sum()
 }
 
-pub fn find_directories_with_min_size(path: &PathBuf, min: usize) -> Vec<PathBuf> {
-    let mut dirs = vec![];
-
-    // if the current directory is smaller than max, add it to the total
-    let size = get_dir_size(path);
-    if size >= min {
-        dirs.push(path.clone());
-    }
-
-    // check subdirectories
-    for entry in std::fs::read_dir(path).unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
-        if path.is_dir() {
-            let res = find_directories_with_min_size(&path, min);
-            dirs.extend(res);
-        }
-    }
-
-    dirs
+pub fn find_directories_with_min_size(fs: &FileSystem, dir: NodeId, min: usize) -> Vec<NodeId> {
+    fs.directories_under(dir)
+        .into_iter()
+        .filter(|&d| get_dir_size(fs, d) >= min)
+        .collect()
 }
 
 pub fn process_part1(input: &str) -> String {
-    // will create the result directory with actual files
-    parse_input(input);
+    let fs = parse_input(input);
 
     // find all of the directories with a total size of at most 100000, then calculate the sum of their total sizes.
-    let total = get_dir_size_max(&PathBuf::from("result"), 100000);
+    let total = get_dir_size_max(&fs, fs.root(), 100000);
 
     total.to_string()
 }
 
 pub fn process_part2(input: &str) -> String {
-    let update_size: usize = 30000000;
-
-    let fs_size: usize = 70000000;
-    let in_use: usize = get_dir_size(&PathBuf::from("result"));
-    let available = fs_size - in_use;
-    let fs_free_required = update_size - available;
-
-    dbg!(fs_size);
-    dbg!(in_use);
-    dbg!(available);
-    dbg!(fs_free_required);
-
-    // will create the result directory with actual files
-    parse_input(input);
-
-    let dirs = find_directories_with_min_size(&PathBuf::from("result"), fs_free_required);
-
-    // find the smallest directory in dirs
-    let mut smallest_dir = dirs[0].clone();
-    let mut smallest_size = get_dir_size(&smallest_dir);
-    for dir in dirs {
-        let size = get_dir_size(&dir);
-        if size < smallest_size {
-            smallest_dir = dir;
-            smallest_size = size;
+    let fs = parse_input(input);
+
+    plan_single(&fs, DISK_SIZE, UPDATE_SIZE)
+        .unwrap()
+        .freed
+        .to_string()
+}
+
+// deletes as little as possible while allowing more than one directory to go
+pub fn process_cleanup(input: &str, disk_size: usize, free: usize) -> String {
+    let fs = parse_input(input);
+
+    match plan_multiple(&fs, disk_size, free) {
+        Some(plan) => {
+            let mut paths = plan.dirs.iter().map(|&d| fs.path(d)).collect::<Vec<_>>();
+            paths.sort();
+            format!("{} ({})", plan.freed, paths.join(", "))
         }
+        None => "not enough space even when deleting everything".to_string(),
     }
-
-    smallest_size.to_string()
 }
 
 #[cfg(test)]
@@ -196,7 +101,6 @@ $ ls
 7214296 k";
 
     #[test]
-    #[ignore]
     pub fn test_part_1() {
         assert_eq!(process_part1(INPUT), "95437");
     }
@@ -205,4 +109,16 @@ $ ls
     pub fn test_part_2() {
         assert_eq!(process_part2(INPUT), "24933642");
     }
+
+    #[test]
+    pub fn test_cleanup() {
+        assert_eq!(
+            process_cleanup(INPUT, DISK_SIZE, UPDATE_SIZE),
+            "24933642 (/d)"
+        );
+        assert_eq!(
+            process_cleanup(INPUT, DISK_SIZE, 46552577),
+            "24934226 (/a/e, /d)"
+        );
+    }
 }
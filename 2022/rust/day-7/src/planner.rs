@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use crate::{
+    find_directories_with_min_size, get_dir_size,
+    vfs::{FileSystem, NodeId},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub dirs: Vec<NodeId>,
+    pub freed: usize,
+}
+
+// how many more bytes have to be freed to have `free` bytes available on a disk of `disk_size`
+pub fn space_to_free(fs: &FileSystem, disk_size: usize, free: usize) -> usize {
+    let in_use = get_dir_size(fs, fs.root());
+    let available = disk_size.saturating_sub(in_use);
+    free.saturating_sub(available)
+}
+
+// the smallest single directory that frees up enough space on its own
+pub fn plan_single(fs: &FileSystem, disk_size: usize, free: usize) -> Option<Plan> {
+    let needed = space_to_free(fs, disk_size, free);
+
+    find_directories_with_min_size(fs, fs.root(), needed)
+        .into_iter()
+        .map(|dir| Plan {
+            dirs: vec![dir],
+            freed: get_dir_size(fs, dir),
+        })
+        .min_by_key(|plan| plan.freed)
+}
+
+// a fixed size set of totals, bit `n` is set when `n` bytes can be freed
+#[derive(Clone)]
+struct Totals {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Totals {
+    fn new(len: usize) -> Self {
+        Totals {
+            words: vec![0; len / 64 + 1],
+            len,
+        }
+    }
+
+    fn insert(&mut self, total: usize) {
+        if total < self.len {
+            self.words[total / 64] |= 1 << (total % 64);
+        }
+    }
+
+    // adds every total in `other`, calling `added` for each one that is new
+    fn absorb(&mut self, other: &Totals, mut added: impl FnMut(usize)) {
+        for (index, (word, &incoming)) in self.words.iter_mut().zip(&other.words).enumerate() {
+            let mut new = incoming & !*word;
+            while new != 0 {
+                added(index * 64 + new.trailing_zeros() as usize);
+                new &= new - 1;
+            }
+            *word |= incoming;
+        }
+    }
+
+    // adds every total in `other` increased by `shift`, dropping whatever ends up past the end
+    fn union_shifted(&mut self, other: &Totals, shift: usize) {
+        let (words, bits) = (shift / 64, shift % 64);
+
+        for index in (words..self.words.len()).rev() {
+            let source = index - words;
+            let mut word = other.words[source] << bits;
+            if bits > 0 && source > 0 {
+                word |= other.words[source - 1] >> (64 - bits);
+            }
+            self.words[index] |= word;
+        }
+
+        // clear the bits past the end so absorb() never sees them
+        let last = self.words.len() - 1;
+        self.words[last] &= (1 << (self.len % 64)) - 1;
+    }
+}
+
+// the set of non-nested directories freeing enough space while deleting as
+// little as possible, this can beat plan_single by combining smaller directories
+//
+// directories are laid out in preorder, where deleting one skips over its
+// whole subtree, which turns it into a subset sum over that sequence: O(n * m / 64)
+// time and O(m * d / 8 + 2 * m) bytes for n directories nested d deep, m being
+// the size of the best single directory
+pub fn plan_multiple(fs: &FileSystem, disk_size: usize, free: usize) -> Option<Plan> {
+    let needed = space_to_free(fs, disk_size, free);
+    if needed == 0 {
+        return Some(Plan {
+            dirs: vec![],
+            freed: 0,
+        });
+    }
+
+    // nothing has to free more than the best single directory does
+    let limit = plan_single(fs, disk_size, free)?.freed;
+
+    let dirs = fs.directories();
+    let sizes = dirs
+        .iter()
+        .map(|&d| get_dir_size(fs, d))
+        .collect::<Vec<_>>();
+    let ends = dirs
+        .iter()
+        .enumerate()
+        .map(|(index, &d)| index + fs.directories_under(d).len())
+        .collect::<Vec<_>>();
+
+    // the totals reachable after deciding on the first i directories only
+    // ever grow with i, so instead of a row per directory we remember the
+    // first position each total shows up at, which is all the walk back needs.
+    // only the current row and the rows deletions jump ahead to are kept,
+    // so at most one per level of nesting
+    assert!(
+        dirs.len() < u16::MAX as usize,
+        "too many directories to plan"
+    );
+    let mut first = vec![u16::MAX; limit + 1];
+    let contains = |first: &[u16], position: usize, total: usize| {
+        total < first.len() && first[total] as usize <= position
+    };
+
+    let mut current = Totals::new(limit + 1);
+    current.insert(0);
+    first[0] = 0;
+    let mut pending: HashMap<usize, Totals> = HashMap::new();
+
+    for index in 0..=dirs.len() {
+        // keeping every earlier directory carries the previous row over,
+        // deletions that skipped a subtree land here
+        if let Some(jumped) = pending.remove(&index) {
+            current.absorb(&jumped, |total| first[total] = index as u16);
+        }
+        if index == dirs.len() {
+            break;
+        }
+
+        // delete this directory and skip past everything inside it
+        pending
+            .entry(ends[index])
+            .or_insert_with(|| Totals::new(limit + 1))
+            .union_shifted(&current, sizes[index]);
+    }
+
+    let freed = (needed..=limit).find(|&total| contains(&first, dirs.len(), total))?;
+
+    // walk back through the decisions that got us to this total
+    let mut plan = vec![];
+    let (mut position, mut total) = (dirs.len(), freed);
+    while position > 0 {
+        if contains(&first, position - 1, total) {
+            position -= 1;
+            continue;
+        }
+
+        let deleted = (0..position)
+            .find(|&i| {
+                ends[i] == position && sizes[i] <= total && contains(&first, i, total - sizes[i])
+            })
+            .unwrap();
+
+        plan.push(dirs[deleted]);
+        total -= sizes[deleted];
+        position = deleted;
+    }
+
+    Some(Plan { dirs: plan, freed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    const INPUT: &str = "$ cd /
+$ ls
+dir a
+dir b
+dir c
+100 x
+$ cd a
+$ ls
+60 y
+$ cd /b
+$ ls
+45 z
+$ cd /c
+$ ls
+dir d
+10 w
+$ cd d
+$ ls
+50 v";
+
+    #[test]
+    pub fn test_plans() {
+        let fs = parse_input(INPUT);
+        let path = |plan: Plan| {
+            let mut paths = plan.dirs.iter().map(|&d| fs.path(d)).collect::<Vec<_>>();
+            paths.sort();
+            (paths, plan.freed)
+        };
+
+        // 265 in use, so 95 has to go
+        assert_eq!(space_to_free(&fs, 300, 130), 95);
+        assert_eq!(
+            path(plan_single(&fs, 300, 130).unwrap()),
+            (vec!["/".to_string()], 265)
+        );
+        assert_eq!(
+            path(plan_multiple(&fs, 300, 130).unwrap()),
+            (vec!["/b".to_string(), "/c/d".to_string()], 95)
+        );
+
+        assert_eq!(plan_multiple(&fs, 500, 100).unwrap().dirs, vec![]);
+        assert!(plan_single(&fs, 100, 1000).is_none());
+    }
+}
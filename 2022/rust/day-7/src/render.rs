@@ -0,0 +1,200 @@
+use crate::{
+    get_dir_size,
+    vfs::{FileSystem, NodeId, NodeKind},
+};
+
+// children sorted by name so the output is stable and two trees can be diffed
+fn sorted_children(fs: &FileSystem, dir: NodeId) -> Vec<NodeId> {
+    let mut children = fs.children(dir).to_vec();
+    children.sort_by(|&a, &b| fs.node(a).name.cmp(&fs.node(b).name));
+    children
+}
+
+fn describe(fs: &FileSystem, id: NodeId) -> String {
+    let node = fs.node(id);
+    match node.kind {
+        NodeKind::Dir { .. } => format!("{} (dir, size={})", node.name, get_dir_size(fs, id)),
+        NodeKind::File { size } => format!("{} (file, size={})", node.name, size),
+    }
+}
+
+// prints the tree below `dir` the way `tree` does, with sizes
+pub fn render_tree(fs: &FileSystem, dir: NodeId) -> String {
+    fn walk(fs: &FileSystem, dir: NodeId, prefix: &str, output: &mut String) {
+        let children = sorted_children(fs, dir);
+
+        for (index, &child) in children.iter().enumerate() {
+            let last = index == children.len() - 1;
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+
+            output.push_str(&format!("{}{}{}\n", prefix, branch, describe(fs, child)));
+            walk(fs, child, &format!("{}{}", prefix, indent), output);
+        }
+    }
+
+    let mut output = describe(fs, dir) + "\n";
+    walk(fs, dir, "", &mut output);
+    output
+}
+
+// sizes the way `du -h` prints them, rounded up to one decimal below 10
+pub fn human_size(size: usize) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut value = size as f64;
+    for unit in UNITS {
+        value /= 1024.0;
+        if value < 1024.0 || unit == "P" {
+            return if value < 10.0 {
+                format!("{:.1}{}", (value * 10.0).ceil() / 10.0, unit)
+            } else {
+                format!("{}{}", value.ceil(), unit)
+            };
+        }
+    }
+
+    unreachable!()
+}
+
+// every directory with its total size, largest first
+pub fn render_du(fs: &FileSystem) -> String {
+    let mut dirs = fs
+        .directories()
+        .into_iter()
+        .map(|dir| (get_dir_size(fs, dir), fs.path(dir)))
+        .collect::<Vec<_>>();
+    dirs.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    dirs.into_iter()
+        .map(|(size, path)| format!("{}\t{}\n", human_size(size), path))
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// the tree below `dir` as pretty printed json
+pub fn to_json(fs: &FileSystem, dir: NodeId) -> String {
+    fn walk(fs: &FileSystem, id: NodeId, depth: usize, output: &mut String) {
+        let indent = "  ".repeat(depth);
+        let node = fs.node(id);
+
+        output.push_str(&format!("{}{{\n", indent));
+        output.push_str(&format!(
+            "{}  \"name\": \"{}\",\n",
+            indent,
+            escape(&node.name)
+        ));
+
+        match node.kind {
+            NodeKind::File { size } => {
+                output.push_str(&format!("{}  \"type\": \"file\",\n", indent));
+                output.push_str(&format!("{}  \"size\": {}\n", indent, size));
+            }
+            NodeKind::Dir { .. } => {
+                output.push_str(&format!("{}  \"type\": \"dir\",\n", indent));
+                output.push_str(&format!(
+                    "{}  \"size\": {},\n",
+                    indent,
+                    get_dir_size(fs, id)
+                ));
+
+                let children = sorted_children(fs, id);
+                if children.is_empty() {
+                    output.push_str(&format!("{}  \"children\": []\n", indent));
+                } else {
+                    output.push_str(&format!("{}  \"children\": [\n", indent));
+                    for (index, &child) in children.iter().enumerate() {
+                        walk(fs, child, depth + 2, output);
+                        if index < children.len() - 1 {
+                            output.push(',');
+                        }
+                        output.push('\n');
+                    }
+                    output.push_str(&format!("{}  ]\n", indent));
+                }
+            }
+        }
+
+        output.push_str(&format!("{}}}", indent));
+    }
+
+    let mut output = String::new();
+    walk(fs, dir, 0, &mut output);
+    output.push('\n');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    const INPUT: &str = "$ cd /
+$ ls
+dir b
+1500 a.txt
+$ cd b
+$ ls
+12 \"q\"";
+
+    #[test]
+    pub fn test_render_tree() {
+        let fs = parse_input(INPUT);
+        assert_eq!(
+            render_tree(&fs, fs.root()),
+            "/ (dir, size=1512)
+├── a.txt (file, size=1500)
+└── b (dir, size=12)
+    └── \"q\" (file, size=12)
+"
+        );
+    }
+
+    #[test]
+    pub fn test_render_du() {
+        let fs = parse_input(INPUT);
+        assert_eq!(render_du(&fs), "1.5K\t/\n12\t/b\n");
+        assert_eq!(human_size(48381165), "47M");
+    }
+
+    #[test]
+    pub fn test_to_json() {
+        let fs = parse_input(INPUT);
+        let b = fs.child(fs.root(), "b").unwrap();
+        assert_eq!(
+            to_json(&fs, b),
+            r#"{
+  "name": "b",
+  "type": "dir",
+  "size": 12,
+  "children": [
+    {
+      "name": "\"q\"",
+      "type": "file",
+      "size": 12
+    }
+  ]
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use std::fmt::{Display, Formatter};
+
+use crate::vfs::{FileSystem, NodeId, NodeKind};
+
+// something in the transcript that doesn't add up, the interpreter carries on
+// with its best guess so one bad line doesn't throw away the whole tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+pub struct Transcript {
+    pub fs: FileSystem,
+    pub issues: Vec<Issue>,
+}
+
+// what resolving a path does about directories that don't exist yet
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Missing {
+    Fail,
+    Create,
+    // create it, but it should have shown up in a listing first
+    Assume,
+}
+
+struct Interpreter {
+    fs: FileSystem,
+    cwd: NodeId,
+    // directory the current `ls` output belongs to
+    listing: Option<NodeId>,
+    issues: Vec<Issue>,
+    line: usize,
+}
+
+// flags that make no difference to the tree we rebuild, like `rm -r`
+fn ignored_flag(command: &str, arg: &str) -> bool {
+    matches!(
+        (command, arg),
+        ("rm", "-r" | "-R" | "-f" | "-rf" | "-fr")
+            | ("mkdir", "-p")
+            | ("ls", "-l" | "-a" | "-la" | "-al")
+    )
+}
+
+impl Interpreter {
+    fn issue(&mut self, message: String) {
+        self.issues.push(Issue {
+            line: self.line,
+            message,
+        });
+    }
+
+    fn child_path(&self, dir: NodeId, name: &str) -> String {
+        let path = self.fs.path(dir);
+        if path.ends_with('/') {
+            path + name
+        } else {
+            path + "/" + name
+        }
+    }
+
+    // walks `path` from the current directory, `missing` decides what happens to directories that aren't there
+    fn resolve(&mut self, path: &str, missing: Missing) -> Option<NodeId> {
+        let mut current = if path.starts_with('/') {
+            self.fs.root()
+        } else {
+            self.cwd
+        };
+
+        for p in path.split('/') {
+            match p {
+                ".." => current = self.fs.parent(current).unwrap_or(current),
+                "." | "" => {}
+                _ => {
+                    if !self.fs.node(current).is_dir() {
+                        self.issue(format!("{} is not a directory", self.fs.path(current)));
+                        return None;
+                    }
+
+                    current = match (self.fs.child(current, p), missing) {
+                        (Some(child), _) => child,
+                        (None, Missing::Create) => self.fs.mkdir(current, p),
+                        (None, Missing::Assume) => {
+                            self.issue(format!("{} was never listed", self.child_path(current, p)));
+                            self.fs.mkdir(current, p)
+                        }
+                        (None, Missing::Fail) => {
+                            self.issue(format!("{} does not exist", path));
+                            return None;
+                        }
+                    };
+                }
+            }
+        }
+
+        Some(current)
+    }
+
+    // like resolve, but the result has to be a directory
+    fn resolve_dir(&mut self, path: &str, missing: Missing) -> Option<NodeId> {
+        let dir = self.resolve(path, missing)?;
+        if !self.fs.node(dir).is_dir() {
+            self.issue(format!("{} is not a directory", self.fs.path(dir)));
+            return None;
+        }
+        Some(dir)
+    }
+
+    // splits a path into the directory it lives in and its final name
+    fn split_path<'a>(&mut self, path: &'a str) -> Option<(NodeId, &'a str)> {
+        let path = path.trim_end_matches('/');
+        let (dir, name) = match path.rsplit_once('/') {
+            Some(("", name)) => (self.fs.root(), name),
+            Some((dir, name)) => (self.resolve_dir(dir, Missing::Fail)?, name),
+            None => (self.cwd, path),
+        };
+
+        if matches!(name, "" | "." | "..") {
+            self.issue(format!("'{}' is not a valid name", path));
+            return None;
+        }
+
+        Some((dir, name))
+    }
+
+    fn command(&mut self, l: &str) {
+        self.listing = None;
+
+        // split on whitespace, skipping the $
+        let mut parts = l.split_whitespace().skip(1);
+        let Some(command) = parts.next() else {
+            self.issue("missing command".to_string());
+            return;
+        };
+        // anything else starting with - is kept and ends up reported as a path
+        let args = parts
+            .filter(|arg| !ignored_flag(command, arg))
+            .collect::<Vec<_>>();
+
+        match (command, &args[..]) {
+            ("cd", [path]) => {
+                if let Some(dir) = self.resolve_dir(path, Missing::Assume) {
+                    self.cwd = dir;
+                }
+            }
+            ("ls", []) => self.listing = Some(self.cwd),
+            ("ls", [path]) => self.listing = self.resolve_dir(path, Missing::Fail),
+            ("mkdir", paths) if !paths.is_empty() => {
+                for path in paths {
+                    self.resolve_dir(path, Missing::Create);
+                }
+            }
+            ("rm", paths) if !paths.is_empty() => {
+                for path in paths {
+                    match self.resolve(path, Missing::Fail) {
+                        Some(node) if node == self.fs.root() => {
+                            self.issue("cannot remove /".to_string())
+                        }
+                        Some(node) => {
+                            // don't leave the shell inside something that no longer exists
+                            if self.fs.is_within(self.cwd, node) {
+                                self.cwd = self.fs.parent(node).unwrap();
+                            }
+                            self.fs.remove(node);
+                        }
+                        None => {}
+                    }
+                }
+            }
+            ("mv", [from, to]) => self.mv(from, to),
+            _ => self.issue(format!("unsupported command '{}'", l)),
+        }
+    }
+
+    fn mv(&mut self, from: &str, to: &str) {
+        let Some(node) = self.resolve(from, Missing::Fail) else {
+            return;
+        };
+
+        // a failed lookup isn't a problem, `to` is allowed to be a new name
+        let reported = self.issues.len();
+        let existing = self.resolve(to, Missing::Fail);
+        self.issues.truncate(reported);
+
+        let (parent, name) = match existing {
+            Some(dir) if self.fs.node(dir).is_dir() => (dir, self.fs.node(node).name.clone()),
+            Some(_) => {
+                self.issue(format!("mv would overwrite {}", to));
+                return;
+            }
+            None => match self.split_path(to) {
+                Some((parent, name)) => (parent, name.to_string()),
+                None => return,
+            },
+        };
+
+        if self.fs.is_within(parent, node) {
+            self.issue(format!("cannot move {} into itself", from));
+            return;
+        }
+
+        if self.fs.child(parent, &name).is_some() {
+            self.issue(format!(
+                "mv would overwrite {}/{}",
+                self.fs.path(parent),
+                name
+            ));
+            return;
+        }
+
+        self.fs.move_to(node, parent, &name);
+    }
+
+    fn output(&mut self, l: &str) {
+        let Some(dir) = self.listing else {
+            self.issue(format!("output '{}' without a listing command", l));
+            return;
+        };
+
+        let Some((kind, name)) = l.split_once(' ') else {
+            self.issue(format!("cannot make sense of '{}'", l));
+            return;
+        };
+
+        let existing = self
+            .fs
+            .child(dir, name)
+            .map(|c| self.fs.node(c).kind.clone());
+
+        if kind == "dir" {
+            match existing {
+                None => {
+                    self.fs.mkdir(dir, name);
+                }
+                Some(NodeKind::Dir { .. }) => {}
+                Some(NodeKind::File { .. }) => self.issue(format!(
+                    "{} listed as a directory but was a file",
+                    self.child_path(dir, name)
+                )),
+            }
+            return;
+        }
+
+        let Ok(size) = kind.parse::<usize>() else {
+            self.issue(format!("cannot make sense of '{}'", l));
+            return;
+        };
+
+        match existing {
+            None => {
+                self.fs.add_file(dir, name, size);
+            }
+            // listing the same directory twice shouldn't count its files twice
+            Some(NodeKind::File { size: previous }) if previous == size => {}
+            Some(NodeKind::File { size: previous }) => {
+                self.issue(format!(
+                    "{} listed with size {} but was {}",
+                    self.child_path(dir, name),
+                    size,
+                    previous
+                ));
+                // the most recent listing wins
+                self.fs.set_size(self.fs.child(dir, name).unwrap(), size);
+            }
+            Some(NodeKind::Dir { .. }) => self.issue(format!(
+                "{} listed as a file but was a directory",
+                self.child_path(dir, name)
+            )),
+        }
+    }
+}
+
+pub fn interpret(input: &str) -> Transcript {
+    let fs = FileSystem::new();
+    let mut interpreter = Interpreter {
+        cwd: fs.root(),
+        fs,
+        listing: None,
+        issues: vec![],
+        line: 0,
+    };
+
+    for (index, l) in input.lines().enumerate() {
+        interpreter.line = index + 1;
+
+        if l.trim().is_empty() {
+            continue;
+        }
+
+        if l.starts_with('$') {
+            interpreter.command(l);
+        } else {
+            interpreter.output(l.trim());
+        }
+    }
+
+    Transcript {
+        fs: interpreter.fs,
+        issues: interpreter.issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_dir_size;
+
+    #[test]
+    pub fn test_repeated_listing() {
+        let transcript = interpret(
+            "$ cd /
+$ ls
+dir a
+100 b
+$ ls
+dir a
+100 b
+$ ls /
+200 b",
+        );
+
+        let fs = &transcript.fs;
+        assert_eq!(get_dir_size(fs, fs.root()), 200);
+        assert_eq!(
+            transcript
+                .issues
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>(),
+            vec!["line 9: /b listed with size 200 but was 100"]
+        );
+    }
+
+    #[test]
+    pub fn test_commands() {
+        let transcript = interpret(
+            "$ mkdir /a/b /c
+$ cd /a/b
+$ ls
+10 x
+20 y
+$ ls ../../c
+5 z
+$ cd /
+$ mv a/b/x c
+$ mv c/x c/w
+$ rm -r a/b/y
+$ mv c a
+$ ls a/c
+5 z
+10 w
+$ mv a a/c
+$ rm /nope
+$ touch q
+$ cd /new/dir
+$ rm -v /c",
+        );
+
+        let fs = &transcript.fs;
+        let c = fs.child(fs.child(fs.root(), "a").unwrap(), "c").unwrap();
+        assert_eq!(fs.path(c), "/a/c");
+        assert!(fs.child(c, "w").is_some());
+        assert!(fs.child(fs.root(), "c").is_none());
+        assert_eq!(get_dir_size(fs, fs.root()), 15);
+
+        let issues = transcript
+            .issues
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            vec![
+                "line 16: cannot move a into itself",
+                "line 17: /nope does not exist",
+                "line 18: unsupported command '$ touch q'",
+                "line 19: /new was never listed",
+                "line 19: /new/dir was never listed",
+                "line 20: -v does not exist",
+                "line 20: /c does not exist",
+            ]
+        );
+    }
+}
@@ -0,0 +1,187 @@
+pub type NodeId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKind {
+    Dir { children: Vec<NodeId> },
+    File { size: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: NodeKind,
+}
+
+impl Node {
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, NodeKind::Dir { .. })
+    }
+}
+
+// an in-memory directory tree, nodes live in a flat arena and refer to each
+// other by index so the tree can be walked both up and down
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        FileSystem::new()
+    }
+}
+
+impl FileSystem {
+    pub fn new() -> Self {
+        FileSystem {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                kind: NodeKind::Dir { children: vec![] },
+            }],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        0
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.nodes[id].kind {
+            NodeKind::Dir { children } => children,
+            NodeKind::File { .. } => &[],
+        }
+    }
+
+    pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        self.children(id)
+            .iter()
+            .copied()
+            .find(|&c| self.nodes[c].name == name)
+    }
+
+    // every directory in the tree, parents before their children
+    pub fn directories(&self) -> Vec<NodeId> {
+        self.directories_under(self.root())
+    }
+
+    // `dir` and every directory below it, parents before their children
+    pub fn directories_under(&self, dir: NodeId) -> Vec<NodeId> {
+        let mut dirs = vec![];
+        let mut stack = vec![dir];
+
+        while let Some(id) = stack.pop() {
+            dirs.push(id);
+            stack.extend(
+                self.children(id)
+                    .iter()
+                    .rev()
+                    .filter(|&&c| self.nodes[c].is_dir()),
+            );
+        }
+
+        dirs
+    }
+
+    pub fn path(&self, id: NodeId) -> String {
+        let mut parts = vec![];
+        let mut current = Some(id);
+
+        while let Some(id) = current {
+            if self.nodes[id].parent.is_some() {
+                parts.push(self.nodes[id].name.as_str());
+            }
+            current = self.nodes[id].parent;
+        }
+
+        parts.reverse();
+        format!("/{}", parts.join("/"))
+    }
+
+    fn add(&mut self, parent: NodeId, name: &str, kind: NodeKind) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+        });
+
+        match &mut self.nodes[parent].kind {
+            NodeKind::Dir { children } => children.push(id),
+            NodeKind::File { .. } => panic!("{} is not a directory", self.path(parent)),
+        }
+
+        id
+    }
+
+    // returns the existing directory if there already is one with this name
+    pub fn mkdir(&mut self, parent: NodeId, name: &str) -> NodeId {
+        match self.child(parent, name) {
+            Some(id) => id,
+            None => self.add(parent, name, NodeKind::Dir { children: vec![] }),
+        }
+    }
+
+    pub fn add_file(&mut self, parent: NodeId, name: &str, size: usize) -> NodeId {
+        self.add(parent, name, NodeKind::File { size })
+    }
+
+    pub fn set_size(&mut self, file: NodeId, size: usize) {
+        if let NodeKind::File { size: current } = &mut self.nodes[file].kind {
+            *current = size;
+        }
+    }
+
+    // true if `node` is `ancestor` or somewhere below it
+    pub fn is_within(&self, node: NodeId, ancestor: NodeId) -> bool {
+        let mut current = Some(node);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.nodes[id].parent;
+        }
+        false
+    }
+
+    fn detach(&mut self, id: NodeId) {
+        if let Some(parent) = self.nodes[id].parent.take() {
+            if let NodeKind::Dir { children } = &mut self.nodes[parent].kind {
+                children.retain(|&c| c != id);
+            }
+        }
+    }
+
+    // unlinks a node and everything below it from the tree, the arena slots are
+    // left behind but can no longer be reached from the root
+    pub fn remove(&mut self, id: NodeId) {
+        assert_ne!(id, self.root(), "cannot remove the root directory");
+        self.detach(id);
+    }
+
+    pub fn move_to(&mut self, id: NodeId, parent: NodeId, name: &str) {
+        assert!(
+            !self.is_within(parent, id),
+            "cannot move {} into itself",
+            self.path(id)
+        );
+
+        self.detach(id);
+        self.nodes[id].name = name.to_string();
+        self.nodes[id].parent = Some(parent);
+
+        match &mut self.nodes[parent].kind {
+            NodeKind::Dir { children } => children.push(id),
+            NodeKind::File { .. } => panic!("{} is not a directory", self.path(parent)),
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::ops::Range;
+
+use crate::Grid;
+
+// the way a tree is looking, as a step across the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Direction {
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Direction {
+    pub const UP: Direction = Direction { dx: 0, dy: -1 };
+    pub const DOWN: Direction = Direction { dx: 0, dy: 1 };
+    pub const LEFT: Direction = Direction { dx: -1, dy: 0 };
+    pub const RIGHT: Direction = Direction { dx: 1, dy: 0 };
+    pub const UP_LEFT: Direction = Direction { dx: -1, dy: -1 };
+    pub const UP_RIGHT: Direction = Direction { dx: 1, dy: -1 };
+    pub const DOWN_LEFT: Direction = Direction { dx: -1, dy: 1 };
+    pub const DOWN_RIGHT: Direction = Direction { dx: 1, dy: 1 };
+
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::UP,
+        Direction::DOWN,
+        Direction::LEFT,
+        Direction::RIGHT,
+    ];
+
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::UP_LEFT,
+        Direction::UP_RIGHT,
+        Direction::DOWN_LEFT,
+        Direction::DOWN_RIGHT,
+    ];
+
+    pub fn all() -> Vec<Direction> {
+        [Direction::CARDINAL, Direction::DIAGONAL].concat()
+    }
+}
+
+// what every tree sees when looking in one direction
+pub struct Scan {
+    pub direction: Direction,
+    // visible from outside the forest on that side
+    pub visible: Grid<bool>,
+    // number of trees visible from the tree before the view is blocked
+    pub distance: Grid<usize>,
+}
+
+fn step(
+    x: usize,
+    y: usize,
+    dx: isize,
+    dy: isize,
+    rows: usize,
+    cols: usize,
+) -> Option<(usize, usize)> {
+    let x = x.checked_add_signed(dx).filter(|&x| x < cols)?;
+    let y = y.checked_add_signed(dy).filter(|&y| y < rows)?;
+    Some((x, y))
+}
+
+// the lines of coordinates to walk for a direction, each starting at the edge
+// the trees look towards, every tree ends up on exactly one line
+fn lines(rows: usize, cols: usize, direction: Direction) -> Vec<Vec<(usize, usize)>> {
+    let Direction { dx, dy } = direction;
+    assert!(dx != 0 || dy != 0, "a direction has to go somewhere");
+
+    let mut lines = vec![];
+    for y in 0..rows {
+        for x in 0..cols {
+            // only start at trees with nothing in front of them
+            if step(x, y, dx, dy, rows, cols).is_some() {
+                continue;
+            }
+
+            let mut line = vec![(x, y)];
+            let mut current = (x, y);
+            while let Some(next) = step(current.0, current.1, -dx, -dy, rows, cols) {
+                line.push(next);
+                current = next;
+            }
+            lines.push(line);
+        }
+    }
+
+    lines
+}
+
+// walks every line once away from the edge, keeping a stack of trees that
+// are still tall enough to block the view of something further in, so every
+// tree is pushed and popped at most once
+pub fn scan(matrix: &Grid<usize>, direction: Direction) -> Scan {
+    let rows = matrix.len();
+    let cols = matrix.first().map_or(0, |r| r.len());
+
+    let mut visible = vec![vec![false; cols]; rows];
+    let mut distance = vec![vec![0; cols]; rows];
+
+    for line in lines(rows, cols, direction) {
+        let mut blockers: Vec<usize> = vec![];
+
+        for (index, &(x, y)) in line.iter().enumerate() {
+            let height = matrix[y][x];
+
+            while let Some(&top) = blockers.last() {
+                let (bx, by) = line[top];
+                if matrix[by][bx] >= height {
+                    break;
+                }
+                blockers.pop();
+            }
+
+            match blockers.last() {
+                Some(&top) => distance[y][x] = index - top,
+                None => {
+                    // nothing in the way all the way to the edge
+                    visible[y][x] = true;
+                    distance[y][x] = index;
+                }
+            }
+
+            blockers.push(index);
+        }
+    }
+
+    Scan {
+        direction,
+        visible,
+        distance,
+    }
+}
+
+pub struct Forest {
+    heights: Grid<usize>,
+    scans: Vec<Scan>,
+}
+
+impl Forest {
+    pub fn new(heights: Grid<usize>, directions: &[Direction]) -> Self {
+        let scans = directions.iter().map(|&d| scan(&heights, d)).collect();
+        Forest { heights, scans }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.heights.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.heights.first().map_or(0, |r| r.len())
+    }
+
+    pub fn height(&self, x: usize, y: usize) -> usize {
+        self.heights[y][x]
+    }
+
+    pub fn directions(&self) -> Vec<Direction> {
+        self.scans.iter().map(|s| s.direction).collect()
+    }
+
+    fn scan_for(&self, direction: Direction) -> Option<&Scan> {
+        self.scans.iter().find(|s| s.direction == direction)
+    }
+
+    // trees that can be seen from outside the forest looking against `direction`
+    pub fn visibility_mask(&self, direction: Direction) -> Option<&Grid<bool>> {
+        self.scan_for(direction).map(|s| &s.visible)
+    }
+
+    pub fn viewing_distances(&self, direction: Direction) -> Option<&Grid<usize>> {
+        self.scan_for(direction).map(|s| &s.distance)
+    }
+
+    pub fn is_visible(&self, x: usize, y: usize) -> bool {
+        self.scans.iter().any(|s| s.visible[y][x])
+    }
+
+    pub fn visible_count(&self) -> usize {
+        (0..self.rows())
+            .flat_map(|y| (0..self.cols()).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.is_visible(x, y))
+            .count()
+    }
+
+    // whether each tree can be seen from outside along any direction
+    pub fn visibility_map(&self) -> Grid<bool> {
+        (0..self.rows())
+            .map(|y| (0..self.cols()).map(|x| self.is_visible(x, y)).collect())
+            .collect()
+    }
+
+    // the viewing distances in every direction multiplied together
+    pub fn scenic_score(&self, x: usize, y: usize) -> usize {
+        self.scans.iter().map(|s| s.distance[y][x]).product()
+    }
+
+    pub fn scenic_scores(&self) -> Grid<usize> {
+        (0..self.rows())
+            .map(|y| (0..self.cols()).map(|x| self.scenic_score(x, y)).collect())
+            .collect()
+    }
+
+    // the tree with the highest scenic score in the region as (x, y, score),
+    // the view itself still reaches outside the region
+    pub fn best_treehouse(
+        &self,
+        xs: Range<usize>,
+        ys: Range<usize>,
+    ) -> Option<(usize, usize, usize)> {
+        let xs = xs.start..xs.end.min(self.cols());
+        let ys = ys.start..ys.end.min(self.rows());
+
+        ys.flat_map(|y| xs.clone().map(move |x| (x, y)))
+            .map(|(x, y)| (x, y, self.scenic_score(x, y)))
+            .max_by_key(|&(x, y, score)| (score, std::cmp::Reverse((y, x))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    const INPUT: &str = "30373
+25512
+65332
+33549
+35390";
+
+    #[test]
+    pub fn test_scan() {
+        let matrix = parse_input(INPUT);
+
+        let up = scan(&matrix, Direction::UP);
+        assert_eq!(up.distance[3][2], 2);
+        assert!(!up.visible[3][2]);
+        assert!(up.visible[3][4]);
+    }
+
+    #[test]
+    pub fn test_diagonals() {
+        let forest = Forest::new(parse_input(INPUT), &Direction::DIAGONAL);
+
+        // the top left 5 looks over everything down to the bottom right corner
+        let down_right = forest.viewing_distances(Direction::DOWN_RIGHT).unwrap();
+        assert_eq!(down_right[1][1], 3);
+        assert_eq!(down_right[2][2], 1);
+        assert!(forest.visibility_mask(Direction::UP).is_none());
+
+        let mask = forest.visibility_mask(Direction::UP_LEFT).unwrap();
+        assert!(mask[2][0] && mask[1][1] && !mask[2][2]);
+    }
+
+    #[test]
+    pub fn test_visibility_map() {
+        let forest = Forest::new(parse_input(INPUT), &Direction::CARDINAL);
+        let map = forest.visibility_map();
+
+        assert_eq!(map.iter().flatten().filter(|&&v| v).count(), 21);
+        assert!(map[1][1] && map[1][2] && !map[1][3] && !map[2][2]);
+    }
+
+    #[test]
+    pub fn test_best_treehouse() {
+        let forest = Forest::new(parse_input(INPUT), &Direction::CARDINAL);
+
+        assert_eq!(forest.best_treehouse(0..5, 0..5), Some((2, 3, 8)));
+        assert_eq!(forest.best_treehouse(0..5, 0..2), Some((2, 1, 4)));
+        assert_eq!(forest.best_treehouse(0..5, 5..9), None);
+    }
+}
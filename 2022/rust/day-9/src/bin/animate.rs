@@ -0,0 +1,43 @@
+use day_09::{
+    trajectory::{record, render_frame, render_visited},
+    Mode,
+};
+use std::{env, fs, thread, time::Duration};
+
+// cargo run --bin animate -- [number of knots] [planar|diagonal|3d] [milliseconds per step] [input file]
+fn main() {
+    let mut args = env::args().skip(1);
+    let knots = args
+        .next()
+        .map_or(10, |a| a.parse::<usize>().expect("Not a number"));
+    let mode = args.next().map_or(Mode::Planar, |a| {
+        a.parse::<Mode>()
+            .unwrap_or_else(|_| panic!("Unknown mode: {} (try planar, diagonal or 3d)", a))
+    });
+    let delay = args
+        .next()
+        .map_or(100, |a| a.parse::<u64>().expect("Not a number"));
+    let path = args.next().unwrap_or_else(|| "./input.txt".to_string());
+
+    let file = fs::read_to_string(path).expect("Unable to read file");
+    let (rope, trajectory) = record(&file, knots, mode);
+    let bounds = trajectory.bounds();
+
+    for (step, frame) in trajectory.frames.iter().enumerate() {
+        // clear the screen and move the cursor back to the top
+        print!("\x1b[2J\x1b[H");
+        match step.checked_sub(1).map(|s| trajectory.motions[s]) {
+            Some(motion) => println!("== {:?} (step {}) ==\n", motion, step),
+            None => println!("== Initial State ==\n"),
+        }
+        print!("{}", render_frame(frame, bounds));
+
+        thread::sleep(Duration::from_millis(delay));
+    }
+
+    println!(
+        "\n== Visited by the tail ({}) ==\n",
+        rope.tail_visited().len()
+    );
+    print!("{}", render_visited(rope.tail_visited(), bounds));
+}
@@ -0,0 +1,24 @@
+use day_09::{simulate, Mode};
+use std::{env, fs};
+
+// cargo run --bin rope -- [number of knots] [planar|diagonal|3d]
+fn main() {
+    let mut args = env::args().skip(1);
+    let knots = args
+        .next()
+        .map_or(10, |a| a.parse::<usize>().expect("Not a number"));
+    let mode = args.next().map_or(Mode::Planar, |a| {
+        a.parse::<Mode>()
+            .unwrap_or_else(|_| panic!("Unknown mode: {} (try planar, diagonal or 3d)", a))
+    });
+
+    let file = fs::read_to_string("./input.txt").expect("Unable to read file");
+    let rope = simulate(&file, knots, mode);
+
+    println!(
+        "{} knots ({:?}): {}",
+        knots,
+        mode,
+        rope.tail_visited().len()
+    );
+}
@@ -1,25 +1,110 @@
 use std::{collections::BTreeSet, str::FromStr};
 
+pub mod trajectory;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Direction {
     Up(i32),
     Down(i32),
     Left(i32),
     Right(i32),
+    UpLeft(i32),
+    UpRight(i32),
+    DownLeft(i32),
+    DownRight(i32),
+    // along the third axis, only used in 3D mode
+    Forward(i32),
+    Backward(i32),
+}
+
+impl Direction {
+    pub fn amount(&self) -> i32 {
+        match *self {
+            Direction::Up(amount) => amount,
+            Direction::Down(amount) => amount,
+            Direction::Left(amount) => amount,
+            Direction::Right(amount) => amount,
+            Direction::UpLeft(amount) => amount,
+            Direction::UpRight(amount) => amount,
+            Direction::DownLeft(amount) => amount,
+            Direction::DownRight(amount) => amount,
+            Direction::Forward(amount) => amount,
+            Direction::Backward(amount) => amount,
+        }
+    }
+
+    // how far a single step in this direction moves
+    pub fn delta(&self) -> Position {
+        let (x, y, z) = match self {
+            Direction::Up(_) => (0, 1, 0),
+            Direction::Down(_) => (0, -1, 0),
+            Direction::Left(_) => (-1, 0, 0),
+            Direction::Right(_) => (1, 0, 0),
+            Direction::UpLeft(_) => (-1, 1, 0),
+            Direction::UpRight(_) => (1, 1, 0),
+            Direction::DownLeft(_) => (-1, -1, 0),
+            Direction::DownRight(_) => (1, -1, 0),
+            Direction::Forward(_) => (0, 0, 1),
+            Direction::Backward(_) => (0, 0, -1),
+        };
+        Position { x, y, z }
+    }
 }
 
 impl FromStr for Direction {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut chars = s.chars();
-        let direction = chars.next().unwrap();
-        let amount = chars.as_str().trim().parse::<i32>().unwrap();
+        let (direction, amount) = s.trim().split_once(' ').ok_or(())?;
+        let amount = amount.trim().parse::<i32>().map_err(|_| ())?;
         match direction {
-            'U' => Ok(Direction::Up(amount)),
-            'D' => Ok(Direction::Down(amount)),
-            'L' => Ok(Direction::Left(amount)),
-            'R' => Ok(Direction::Right(amount)),
+            "U" => Ok(Direction::Up(amount)),
+            "D" => Ok(Direction::Down(amount)),
+            "L" => Ok(Direction::Left(amount)),
+            "R" => Ok(Direction::Right(amount)),
+            "UL" => Ok(Direction::UpLeft(amount)),
+            "UR" => Ok(Direction::UpRight(amount)),
+            "DL" => Ok(Direction::DownLeft(amount)),
+            "DR" => Ok(Direction::DownRight(amount)),
+            "F" => Ok(Direction::Forward(amount)),
+            "B" => Ok(Direction::Backward(amount)),
+            _ => Err(()),
+        }
+    }
+}
+
+// which motions a simulation accepts, the physics are the same for all of them
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    // up, down, left and right like the puzzle
+    Planar,
+    // planar plus the four diagonals
+    Diagonal,
+    // the six directions along the x, y and z axes
+    Spatial,
+}
+
+impl Mode {
+    pub fn allows(&self, direction: &Direction) -> bool {
+        let delta = direction.delta();
+        match self {
+            Mode::Planar => delta.z == 0 && (delta.x == 0 || delta.y == 0),
+            Mode::Diagonal => delta.z == 0,
+            Mode::Spatial => {
+                (delta.x != 0) as i32 + (delta.y != 0) as i32 + (delta.z != 0) as i32 == 1
+            }
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "planar" | "2d" => Ok(Mode::Planar),
+            "diagonal" => Ok(Mode::Diagonal),
+            "spatial" | "3d" => Ok(Mode::Spatial),
             _ => Err(()),
         }
     }
@@ -29,161 +114,140 @@ impl FromStr for Direction {
 pub struct Position {
     pub x: i32,
     pub y: i32,
+    pub z: i32,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position::new()
+    }
 }
 
 impl Position {
     pub fn new() -> Position {
-        Position { x: 0, y: 0 }
+        Position { x: 0, y: 0, z: 0 }
     }
 
+    // chebyshev distance, diagonal neighbours are 1 away just like straight ones
     pub fn distance(&self, other: &Position) -> i32 {
-        let x_diff = self.x - other.x;
-        let y_diff = self.y - other.y;
-        ((x_diff.pow(2) + y_diff.pow(2)) as f64).sqrt().floor() as i32
+        (self.x - other.x)
+            .abs()
+            .max((self.y - other.y).abs())
+            .max((self.z - other.z).abs())
+    }
+
+    pub fn is_touching(&self, other: &Position) -> bool {
+        self.distance(other) <= 1
     }
 
     pub fn follow(&mut self, other: &Position) -> Vec<Position> {
         let mut result = vec![];
-        while self.distance(other) > 1 {
-            let mut new_x = self.x;
-            let mut new_y = self.y;
+        while !self.is_touching(other) {
+            // one step closer along every axis we're not lined up on yet
+            self.x += (other.x - self.x).signum();
+            self.y += (other.y - self.y).signum();
+            self.z += (other.z - self.z).signum();
 
-            if other.x > self.x {
-                new_x += 1;
-            }
-            if other.x < self.x {
-                new_x -= 1;
-            }
-
-            if other.y > self.y {
-                new_y += 1;
-            }
-            if other.y < self.y {
-                new_y -= 1;
-            }
-
-            self.x = new_x;
-            self.y = new_y;
-
-            result.push(self.clone());
+            result.push(*self);
         }
         result
     }
 }
 
-#[derive(Debug)]
-pub struct Head {
-    pub position: Position,
+#[derive(Debug, Clone)]
+pub struct Rope {
+    // the head is the first knot, the tail the last
+    pub knots: Vec<Position>,
+    // every position each knot has been in, indexed like knots
+    pub visited: Vec<BTreeSet<Position>>,
 }
 
-impl Head {
-    pub fn new() -> Head {
-        Head {
-            position: Position { x: 0, y: 0 },
+impl Rope {
+    pub fn new(length: usize) -> Rope {
+        assert!(length > 0, "a rope needs at least one knot");
+
+        Rope {
+            knots: vec![Position::new(); length],
+            visited: vec![BTreeSet::from([Position::new()]); length],
         }
     }
 
+    pub fn head(&self) -> Position {
+        self.knots[0]
+    }
+
+    pub fn tail(&self) -> Position {
+        self.knots[self.knots.len() - 1]
+    }
+
+    pub fn tail_visited(&self) -> &BTreeSet<Position> {
+        &self.visited[self.knots.len() - 1]
+    }
+
+    // moves the head a single step and lets every other knot catch up
     pub fn step(&mut self, direction: Direction) {
-        match direction {
-            Direction::Up(_) => self.position.y += 1,
-            Direction::Down(_) => self.position.y -= 1,
-            Direction::Left(_) => self.position.x -= 1,
-            Direction::Right(_) => self.position.x += 1,
+        let head = &mut self.knots[0];
+        let delta = direction.delta();
+        head.x += delta.x;
+        head.y += delta.y;
+        head.z += delta.z;
+        self.visited[0].insert(*head);
+
+        for index in 1..self.knots.len() {
+            let leader = self.knots[index - 1];
+            let moved = self.knots[index].follow(&leader);
+
+            // nobody further down the rope will move either
+            if moved.is_empty() {
+                break;
+            }
+            self.visited[index].extend(moved);
         }
     }
-}
 
-#[derive(Debug)]
-pub struct Tail {
-    pub position: Position,
-    pub visited: Vec<Position>,
-}
-
-impl Tail {
-    pub fn new() -> Tail {
-        let mut visited = Vec::new();
-        visited.push(Position { x: 0, y: 0 });
-        Tail {
-            position: Position { x: 0, y: 0 },
-            visited,
+    // moves the head as many steps as the direction says
+    pub fn apply(&mut self, direction: Direction) {
+        for _ in 0..direction.amount() {
+            self.step(direction);
         }
     }
 }
 
-pub fn process_part1(input: &str) -> String {
-    let mut head = Head::new();
-    let mut tail = Tail::new();
-
-    let mut unique: BTreeSet<Position> = BTreeSet::new();
-    unique.insert(Position::new());
+// one motion per line, panicking on anything the mode doesn't allow
+pub fn parse_motions(input: &str, mode: Mode) -> impl Iterator<Item = Direction> + '_ {
+    input.lines().map(move |line| {
+        let direction = line
+            .parse::<Direction>()
+            .unwrap_or_else(|_| panic!("Invalid motion: {}", line));
+        if !mode.allows(&direction) {
+            panic!("{} is not allowed in {:?} mode", line, mode);
+        }
+        direction
+    })
+}
 
-    input.lines().for_each(|line| {
-        let direction = line.parse::<Direction>().unwrap();
-        let steps = match direction {
-            Direction::Up(amount) => amount,
-            Direction::Down(amount) => amount,
-            Direction::Left(amount) => amount,
-            Direction::Right(amount) => amount,
-        };
+pub fn simulate(input: &str, knots: usize, mode: Mode) -> Rope {
+    let mut rope = Rope::new(knots);
 
-        for _ in 0..steps {
-            head.step(direction);
-            let visited = tail.position.follow(&head.position);
-            visited.iter().for_each(|pos| {
-                unique.insert(pos.clone());
-            });
-        }
-    });
+    for direction in parse_motions(input, mode) {
+        rope.apply(direction);
+    }
 
-    let amount = unique.len();
+    rope
+}
 
-    amount.to_string()
+pub fn process_part1(input: &str) -> String {
+    simulate(input, 2, Mode::Planar)
+        .tail_visited()
+        .len()
+        .to_string()
 }
 
 pub fn process_part2(input: &str) -> String {
-    let mut head = Head::new();
-    let mut knot1 = Position::new();
-    let mut knot2 = Position::new();
-    let mut knot3 = Position::new();
-    let mut knot4 = Position::new();
-    let mut knot5 = Position::new();
-    let mut knot6 = Position::new();
-    let mut knot7 = Position::new();
-    let mut knot8 = Position::new();
-    let mut tail = Tail::new();
-
-    let mut unique: BTreeSet<Position> = BTreeSet::new();
-    unique.insert(Position::new());
-
-    input.lines().for_each(|line| {
-        let direction = line.parse::<Direction>().unwrap();
-        let steps = match direction {
-            Direction::Up(amount) => amount,
-            Direction::Down(amount) => amount,
-            Direction::Left(amount) => amount,
-            Direction::Right(amount) => amount,
-        };
-
-        for _ in 0..steps {
-            head.step(direction);
-            knot1.follow(&head.position);
-            knot2.follow(&knot1);
-            knot3.follow(&knot2);
-            knot4.follow(&knot3);
-            knot5.follow(&knot4);
-            knot6.follow(&knot5);
-            knot7.follow(&knot6);
-            knot8.follow(&knot7);
-            let visited = tail.position.follow(&knot8);
-            visited.iter().for_each(|pos| {
-                unique.insert(pos.clone());
-            });
-        }
-    });
-
-    let amount = unique.len();
-
-    amount.to_string()
+    simulate(input, 10, Mode::Planar)
+        .tail_visited()
+        .len()
+        .to_string()
 }
 
 #[cfg(test)]
@@ -218,4 +282,43 @@ U 20";
 
         assert_eq!(process_part2(input), "36");
     }
+
+    #[test]
+    pub fn test_rope() {
+        let mut rope = Rope::new(3);
+        rope.apply(Direction::Right(4));
+
+        assert_eq!(rope.head(), Position { x: 4, y: 0, z: 0 });
+        assert_eq!(rope.tail(), Position { x: 2, y: 0, z: 0 });
+        assert_eq!(rope.visited[0].len(), 5);
+        assert_eq!(rope.visited[1].len(), 4);
+        assert_eq!(rope.tail_visited().len(), 3);
+
+        // a single knot rope is just the head
+        let rope = simulate("U 2\nL 1", 1, Mode::Planar);
+        assert_eq!(rope.tail(), Position { x: -1, y: 2, z: 0 });
+    }
+
+    #[test]
+    pub fn test_diagonal_motions() {
+        // a diagonal head drags the tail along the diagonal
+        let rope = simulate("UR 3\nDR 1", 2, Mode::Diagonal);
+        assert_eq!(rope.head(), Position { x: 4, y: 2, z: 0 });
+        assert_eq!(rope.tail(), Position { x: 3, y: 2, z: 0 });
+        assert_eq!(rope.tail_visited().len(), 4);
+    }
+
+    #[test]
+    pub fn test_spatial_motions() {
+        let rope = simulate("F 3\nU 1\nR 1\nB 1", 2, Mode::Spatial);
+        assert_eq!(rope.head(), Position { x: 1, y: 1, z: 2 });
+        assert_eq!(rope.tail(), Position { x: 0, y: 0, z: 2 });
+        assert_eq!(rope.tail_visited().len(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_mode_rejects_motion() {
+        simulate("UL 1", 2, Mode::Planar);
+    }
 }
@@ -0,0 +1,185 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::{parse_motions, Direction, Mode, Position, Rope};
+
+// every knot's position after every single step of the head
+#[derive(Debug, Clone, Default)]
+pub struct Trajectory {
+    // frames[0] is the starting position, one more frame per step after that
+    pub frames: Vec<Vec<Position>>,
+    // the motion each step belonged to, indexed like frames[1..]
+    pub motions: Vec<Direction>,
+}
+
+impl Trajectory {
+    // positions of a single knot over time
+    pub fn knot(&self, index: usize) -> impl Iterator<Item = Position> + '_ {
+        self.frames.iter().map(move |frame| frame[index])
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::around(self.frames.iter().flatten())
+    }
+}
+
+pub fn record(input: &str, knots: usize, mode: Mode) -> (Rope, Trajectory) {
+    let mut rope = Rope::new(knots);
+    let mut trajectory = Trajectory {
+        frames: vec![rope.knots.clone()],
+        motions: vec![],
+    };
+
+    for direction in parse_motions(input, mode) {
+        for _ in 0..direction.amount() {
+            rope.step(direction);
+            trajectory.frames.push(rope.knots.clone());
+            trajectory.motions.push(direction);
+        }
+    }
+
+    (rope, trajectory)
+}
+
+// the area to draw, always including the start; 3D ropes are drawn looking down the z axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_y: i32,
+    pub max_y: i32,
+}
+
+impl Bounds {
+    pub fn around<'a>(positions: impl IntoIterator<Item = &'a Position>) -> Bounds {
+        let start = Position::new();
+        positions.into_iter().fold(
+            Bounds {
+                min_x: start.x,
+                max_x: start.x,
+                min_y: start.y,
+                max_y: start.y,
+            },
+            |b, p| Bounds {
+                min_x: b.min_x.min(p.x),
+                max_x: b.max_x.max(p.x),
+                min_y: b.min_y.min(p.y),
+                max_y: b.max_y.max(p.y),
+            },
+        )
+    }
+
+    // rows top to bottom, up is positive y like in the puzzle
+    fn draw(&self, cell: impl Fn(i32, i32) -> char) -> String {
+        let mut output = String::new();
+        for y in (self.min_y..=self.max_y).rev() {
+            for x in self.min_x..=self.max_x {
+                output.push(cell(x, y));
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+// H for the head, T for the tail of a two knot rope, otherwise the knot number
+fn label(index: usize, knots: usize) -> char {
+    match index {
+        0 => 'H',
+        _ if knots == 2 => 'T',
+        1..=9 => char::from_digit(index as u32, 10).unwrap(),
+        _ => '+',
+    }
+}
+
+// draws the rope like the puzzle does, knots closer to the head cover the ones behind them
+pub fn render_frame(knots: &[Position], bounds: Bounds) -> String {
+    bounds.draw(|x, y| {
+        knots
+            .iter()
+            .position(|k| k.x == x && k.y == y)
+            .map(|index| label(index, knots.len()))
+            .unwrap_or(if x == 0 && y == 0 { 's' } else { '.' })
+    })
+}
+
+// every visited cell as #, with the start marked as s
+pub fn render_visited(visited: &BTreeSet<Position>, bounds: Bounds) -> String {
+    let cells: HashSet<(i32, i32)> = visited.iter().map(|p| (p.x, p.y)).collect();
+    bounds.draw(|x, y| {
+        if x == 0 && y == 0 {
+            's'
+        } else if cells.contains(&(x, y)) {
+            '#'
+        } else {
+            '.'
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2";
+
+    #[test]
+    pub fn test_record() {
+        let (rope, trajectory) = record(INPUT, 2, Mode::Planar);
+
+        assert_eq!(trajectory.frames.len(), 25);
+        assert_eq!(trajectory.motions.len(), 24);
+        assert_eq!(trajectory.knot(1).last(), Some(rope.tail()));
+        assert_eq!(
+            trajectory.knot(1).collect::<BTreeSet<_>>(),
+            *rope.tail_visited()
+        );
+    }
+
+    #[test]
+    pub fn test_render() {
+        let (rope, trajectory) = record(INPUT, 2, Mode::Planar);
+        let bounds = trajectory.bounds();
+
+        assert_eq!(
+            render_frame(&trajectory.frames[6], bounds),
+            "......
+......
+....H.
+....T.
+s.....
+"
+        );
+
+        assert_eq!(
+            render_visited(rope.tail_visited(), bounds),
+            "..##..
+...##.
+.####.
+....#.
+s###..
+"
+        );
+    }
+
+    #[test]
+    pub fn test_render_many_knots() {
+        let (_, trajectory) = record("R 4\nU 4", 10, Mode::Planar);
+
+        assert_eq!(
+            render_frame(&trajectory.frames[8], trajectory.bounds()),
+            "....H
+....1
+..432
+.5...
+6....
+"
+        );
+    }
+}